@@ -0,0 +1,219 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::series::SeriesResult;
+
+// Value with a guaranteed bound of its absolute error, propagated through
+// the arithmetic and the elementary functions, rounding errors are not
+// included. sensitivities[i] is the first order factor by which an error of
+// the budgeted sub-computation i enters the error of the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounded {
+    pub value: f64,
+    pub error: f64,
+    pub sensitivities: Vec<f64>,
+}
+
+impl Bounded {
+    pub fn exact(value: f64) -> Self {
+        Self::with_error(value, 0.)
+    }
+
+    // an input known up to error
+    pub fn with_error(value: f64, error: f64) -> Self {
+        Bounded {
+            value,
+            error: error.abs(),
+            sensitivities: vec![],
+        }
+    }
+
+    // the result of the budgeted sub-computation number source, error is the
+    // accuracy it was computed with
+    pub fn source(value: f64, error: f64, source: usize) -> Self {
+        let mut sensitivities = vec![0.; source + 1];
+        sensitivities[source] = 1.;
+        Bounded {
+            value,
+            error: error.abs(),
+            sensitivities,
+        }
+    }
+
+    pub fn from_series(result: SeriesResult, source: usize) -> Self {
+        Self::source(result.value, result.bound, source)
+    }
+
+    pub fn sensitivity(&self, source: usize) -> f64 {
+        self.sensitivities.get(source).copied().unwrap_or(0.)
+    }
+
+    // f(self) with f' at the value as derivative and error as the bound of
+    // |f(y) - f(value)| for |y - value| <= self.error
+    fn map(&self, value: f64, derivative: f64, error: f64) -> Self {
+        Bounded {
+            value,
+            error,
+            sensitivities: scaled(&self.sensitivities, derivative.abs()),
+        }
+    }
+
+    // for a monotone f the error is taken at the ends of the interval
+    fn map_monotone(&self, f: impl Fn(f64) -> f64, derivative: f64) -> Self {
+        let value = f(self.value);
+        let error = (f(self.value + self.error) - value)
+            .abs()
+            .max((value - f(self.value - self.error)).abs());
+        self.map(value, derivative, error)
+    }
+
+    pub fn sin(&self) -> Self {
+        self.map(self.value.sin(), self.value.cos(), self.error.min(2.))
+    }
+
+    pub fn cos(&self) -> Self {
+        self.map(self.value.cos(), self.value.sin(), self.error.min(2.))
+    }
+
+    pub fn atan(&self) -> Self {
+        self.map_monotone(f64::atan, 1. / (1. + self.value * self.value))
+    }
+
+    pub fn exp(&self) -> Self {
+        self.map_monotone(f64::exp, self.value.exp())
+    }
+
+    // the error is infinite if the interval reaches zero
+    pub fn ln(&self) -> Self {
+        if self.value - self.error <= 0. {
+            return self.map(self.value.ln(), 1. / self.value, f64::INFINITY);
+        }
+        self.map_monotone(f64::ln, 1. / self.value)
+    }
+
+    // the interval is cut at zero
+    pub fn sqrt(&self) -> Self {
+        let value = self.value.sqrt();
+        let error = (value - (self.value - self.error).max(0.).sqrt())
+            .max((self.value + self.error).sqrt() - value);
+        self.map(value, 0.5 / value, error)
+    }
+}
+
+impl From<f64> for Bounded {
+    fn from(value: f64) -> Self {
+        Self::exact(value)
+    }
+}
+
+fn scaled(sensitivities: &[f64], factor: f64) -> Vec<f64> {
+    sensitivities.iter().map(|s| s * factor).collect()
+}
+
+// first_factor first + second_factor second, the shorter one is padded
+fn combined(first: &[f64], first_factor: f64, second: &[f64], second_factor: f64) -> Vec<f64> {
+    (0..first.len().max(second.len()))
+        .map(|i| {
+            first.get(i).unwrap_or(&0.) * first_factor
+                + second.get(i).unwrap_or(&0.) * second_factor
+        })
+        .collect()
+}
+
+impl Neg for Bounded {
+    type Output = Bounded;
+
+    fn neg(self) -> Self::Output {
+        Bounded {
+            value: -self.value,
+            ..self
+        }
+    }
+}
+
+impl Add for Bounded {
+    type Output = Bounded;
+
+    fn add(self, rhs: Bounded) -> Self::Output {
+        Bounded {
+            value: self.value + rhs.value,
+            error: self.error + rhs.error,
+            sensitivities: combined(&self.sensitivities, 1., &rhs.sensitivities, 1.),
+        }
+    }
+}
+
+impl Sub for Bounded {
+    type Output = Bounded;
+
+    fn sub(self, rhs: Bounded) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl Mul for Bounded {
+    type Output = Bounded;
+
+    // |ab - a'b'| <= |a| db + |b| da + da db
+    fn mul(self, rhs: Bounded) -> Self::Output {
+        let (a, b) = (self.value.abs(), rhs.value.abs());
+        Bounded {
+            value: self.value * rhs.value,
+            error: a * rhs.error + b * self.error + self.error * rhs.error,
+            sensitivities: combined(&self.sensitivities, b, &rhs.sensitivities, a),
+        }
+    }
+}
+
+impl Div for Bounded {
+    type Output = Bounded;
+
+    // |a/b - a'/b'| <= (|a| db + |b| da) / (|b| (|b| - db)), infinite if the
+    // interval of b reaches zero
+    fn div(self, rhs: Bounded) -> Self::Output {
+        let (a, b) = (self.value.abs(), rhs.value.abs());
+        let error = if b > rhs.error {
+            (a * rhs.error + b * self.error) / (b * (b - rhs.error))
+        } else {
+            f64::INFINITY
+        };
+        Bounded {
+            value: self.value / rhs.value,
+            error,
+            sensitivities: combined(&self.sensitivities, 1. / b, &rhs.sensitivities, a / (b * b)),
+        }
+    }
+}
+
+// Accuracies of the sources sub-computations of evaluate so that the error
+// of its result is at most target. The part of the target left by the inputs
+// is split evenly by the first order sensitivities, then the guaranteed
+// bound is checked and the accuracies are shrunk while it is too large.
+// None if the inputs alone exceed the target.
+pub fn split_accuracy(
+    target: f64,
+    sources: usize,
+    evaluate: impl Fn(&[f64]) -> Bounded,
+) -> Option<Vec<f64>> {
+    let probe = evaluate(&vec![target; sources]);
+    let budgeted: f64 = (0..sources).map(|i| probe.sensitivity(i) * target).sum();
+    let remaining = target - (probe.error - budgeted).max(0.);
+    if remaining <= 0. {
+        return None;
+    }
+
+    let mut accuracies: Vec<f64> = (0..sources)
+        .map(|i| match probe.sensitivity(i) {
+            s if s > 0. => remaining / (sources as f64 * s),
+            _ => target,
+        })
+        .collect();
+
+    // the second order terms are not in the sensitivities
+    for _ in 0..64 {
+        if evaluate(&accuracies).error <= target {
+            return Some(accuracies);
+        }
+        accuracies.iter_mut().for_each(|accuracy| *accuracy /= 2.);
+    }
+    None
+}
@@ -0,0 +1,4 @@
+pub mod bounded;
+pub mod roots;
+pub mod series;
+pub mod table;
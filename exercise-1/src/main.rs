@@ -1,95 +1,304 @@
-fn taylor_sin(acc: f64, x: f64) -> f64 {
-    let mut uk = x;
-    let mut r = uk;
-    let mut k = 1.;
-
-    while uk.abs() > acc {
-        uk *= -(x * x) / ((2. * k + 1.) * (2. * k));
-        k += 1.;
-        r += uk;
-    }
+use std::{env::args, io::stdout, process::exit};
 
-    r
-}
+use exercise_1::{
+    bounded::{split_accuracy, Bounded},
+    roots,
+    series::{self, SeriesResult},
+    table::{Column, Format, Table},
+};
+use exercise_2::{
+    basic::{ElementaryOps, OtherNumericalOps},
+    double_double::DoubleDouble,
+};
 
-fn geron_sqrt(acc: f64, x: f64) -> f64 {
-    let mut pthis = x + 0.5;
-    let mut pnext = (pthis + (x / pthis)) / 2.;
+struct Computation<S = f64> {
+    u: S,
+    v: S,
+    z: S,
+}
 
-    while (pnext - pthis).abs() > acc {
-        pthis = pnext;
-        pnext = (pnext + (x / pnext)) / 2.;
-    }
+struct Budget {
+    du: f64,
+    dv: f64,
+}
 
-    pnext
+fn v_argument(x: f64) -> f64 {
+    1.0 + x - 12. * x * x
 }
 
-struct Computation {
-    u: f64,
-    v: f64,
-    z: f64,
+// z = u / v with u = sin(4.5x + 0.6) and v = sqrt(1 + x - 12x^2) as the
+// budgeted sub-computations 0 and 1
+fn bounded_z(accuracies: &[f64], x: f64) -> (Bounded, Bounded) {
+    let u = Bounded::from_series(series::sin(accuracies[0], 4.5 * x + 0.6), 0);
+    // approx_z only calls it for a positive argument of v
+    let root = roots::sqrt(accuracies[1], v_argument(x))
+        .expect("the argument of v is positive and finite");
+    let v = Bounded::source(root.value, root.bound, 1);
+    (u, v)
 }
 
-fn approx_z(acc: f64, x: f64) -> Computation {
-    let sin_acc = acc / 2.5;
-    let sqrt_acc = acc / 2.;
+// Err for x outside of -1/4 < x < 1/3, where v is not positive
+fn approx_z(acc: f64, x: f64) -> Result<(Computation, Budget), String> {
+    let argument = v_argument(x);
+    if argument <= 0. {
+        return Err(format!(
+            "z is not defined at x = {x}, 1 + x - 12x^2 = {argument} is not positive"
+        ));
+    }
 
-    let u = taylor_sin(sin_acc, 4.5 * x + 0.6);
-    let v = geron_sqrt(sqrt_acc, 1.0 + x - 12. * x * x);
-    let z = u / v;
+    let accuracies = split_accuracy(acc, 2, |accuracies| {
+        let (u, v) = bounded_z(accuracies, x);
+        u / v
+    })
+    .ok_or_else(|| format!("z can not be computed at x = {x} with the accuracy {acc}"))?;
 
-    Computation { u, v, z }
+    let (u, v) = bounded_z(&accuracies, x);
+    let z = u.value / v.value;
+
+    Ok((
+        Computation {
+            u: u.value,
+            v: v.value,
+            z,
+        },
+        Budget {
+            du: accuracies[0],
+            dv: accuracies[1],
+        },
+    ))
 }
 
-fn compare_z(x: f64) -> Computation {
-    let u = (4.5 * x + 0.6).sin();
-    let v = (1.0 + x - 12. * x * x).sqrt();
+// the reference values in double-double, about 32 digits
+fn compare_z(x: f64) -> Computation<DoubleDouble> {
+    let x = DoubleDouble::from(x);
+    let int = DoubleDouble::from;
+    let argument = int(9) / int(2) * x + int(3) / int(5);
+    let u = series::sin(DoubleDouble::from(DoubleDouble::EPSILON), argument).value;
+    let v = (int(1) + x - int(12) * x * x).sqrt_trait();
     let z = u / v;
 
     Computation { u, v, z }
 }
 
-const EPSILON: f64 = 0.00_000_1;
-const U_ACC: f64 = EPSILON / 2.5;
-const V_ACC: f64 = EPSILON / 2.;
+// reference - value, exact up to the error of the reference
+fn deviation(reference: DoubleDouble, value: f64) -> f64 {
+    f64::from(reference - DoubleDouble::from(value))
+}
 
-fn main() {
-    let x_start = 0.1;
-    let x_end = 0.2;
-    let x_step = 0.01;
-    let n_max = (((x_end - x_start) / x_step) as f64).ceil() as usize;
-
-    println!(
-        "x,u,\\Delta_u,\\bar{{u}},\\bar{{\\Delta_u}},\
-             v,\\Delta_v,\\bar{{v}},\\bar{{\\Delta_v}},\
-             z,\\Delta_z,\\bar{{z}},\\bar{{\\Delta_z}}"
-    );
-
-    for n in 0..=n_max {
-        let x = x_start + (n as f64) * x_step;
-        let approx_computation = approx_z(EPSILON, x);
-        let compare_computation = compare_z(x);
-
-        let u = approx_computation.u;
-        let v = approx_computation.v;
-        let z = approx_computation.z;
-
-        let bu = compare_computation.u;
-        let bv = compare_computation.v;
-        let bz = compare_computation.z;
-
-        let du = U_ACC;
-        let dv = V_ACC;
-        let dz = EPSILON;
-
-        let bdu = bu - u;
-        let bdv = bv - v;
-        let bdz = bz - z;
-
-        println!(
-            "{x},{u},{du},{bu},{bdu},\
-                 {v},{dv},{bv},{bdv},\
-                 {z},{dz},{bz},{bdz}"
-        );
+const USAGE: &str = "[--from <x>] [--to <x>] [--step <h>] [--accuracy <e>] \
+     [--function z|sin|cos|exp|ln1p|atan] [--format csv|latex|markdown]";
+
+#[derive(Debug, Clone)]
+struct Options {
+    x_start: f64,
+    x_end: f64,
+    x_step: f64,
+    accuracy: f64,
+    function: String,
+    format: Format,
+}
+
+fn parse_number(option: &str, value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => Ok(number),
+        _ => Err(format!("invalid value {value} for {option}")),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        x_start: 0.1,
+        x_end: 0.2,
+        x_step: 0.01,
+        accuracy: 0.000_001,
+        function: String::from("z"),
+        format: Format::Csv,
+    };
+
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {option}"))?;
+
+        match option.as_str() {
+            "--from" => options.x_start = parse_number(option, value)?,
+            "--to" => options.x_end = parse_number(option, value)?,
+            "--step" => options.x_step = parse_number(option, value)?,
+            "--accuracy" => options.accuracy = parse_number(option, value)?,
+            "--function" => {
+                if !["z", "sin", "cos", "exp", "ln1p", "atan"].contains(&value.as_str()) {
+                    return Err(format!("unknown function {value}"));
+                }
+                options.function = value.clone();
+            }
+            "--format" => {
+                options.format =
+                    Format::parse(value).ok_or_else(|| format!("unknown format {value}"))?
+            }
+            _ => return Err(format!("unknown option {option}")),
+        }
+    }
+
+    if options.x_step <= 0. {
+        return Err(format!("step has to be positive, got {}", options.x_step));
+    }
+    if options.x_end < options.x_start {
+        return Err(String::from("the range is empty"));
     }
+    if options.accuracy <= 0. {
+        return Err(format!(
+            "accuracy has to be positive, got {}",
+            options.accuracy
+        ));
+    }
+
+    Ok(options)
+}
+
+fn tabulate_z(accuracy: f64, points: &[f64]) -> Result<Table, String> {
+    let mut table = Table::new(vec![
+        Column::new("x", "x"),
+        Column::new("u", "u"),
+        Column::new("du", "\\Delta_u"),
+        Column::new("bu", "\\bar{u}"),
+        Column::new("bdu", "\\bar{\\Delta_u}"),
+        Column::new("v", "v"),
+        Column::new("dv", "\\Delta_v"),
+        Column::new("bv", "\\bar{v}"),
+        Column::new("bdv", "\\bar{\\Delta_v}"),
+        Column::new("z", "z"),
+        Column::new("dz", "\\Delta_z"),
+        Column::new("bz", "\\bar{z}"),
+        Column::new("bdz", "\\bar{\\Delta_z}"),
+    ]);
+
+    for x in points.iter().copied() {
+        let (Computation { u, v, z }, budget) = approx_z(accuracy, x)?;
+        let Computation {
+            u: bu,
+            v: bv,
+            z: bz,
+        } = compare_z(x);
+
+        table.push(vec![
+            x,
+            u,
+            budget.du,
+            f64::from(bu),
+            deviation(bu, u),
+            v,
+            budget.dv,
+            f64::from(bv),
+            deviation(bv, v),
+            z,
+            accuracy,
+            f64::from(bz),
+            deviation(bz, z),
+        ]);
+    }
+
+    Ok(table)
+}
+
+// a series function with the bound and the number of its terms against the
+// double-double function
+fn tabulate_series(
+    accuracy: f64,
+    points: &[f64],
+    (name, latex): (&str, &str),
+    f: fn(f64, f64) -> SeriesResult,
+    compare: fn(&DoubleDouble) -> DoubleDouble,
+) -> Table {
+    let mut table = Table::new(vec![
+        Column::new("x", "x"),
+        Column::new(name, latex),
+        Column::new("terms", "n"),
+        Column::new("bound", "\\Delta"),
+        Column::new(&format!("b{name}"), &format!("\\overline{{{latex}}}")),
+        Column::new("berror", "\\bar{\\Delta}"),
+    ]);
+
+    for x in points.iter().copied() {
+        let result = f(accuracy, x);
+        let reference = compare(&DoubleDouble::from(x));
+        table.push(vec![
+            x,
+            result.value,
+            result.terms as f64,
+            result.bound,
+            f64::from(reference),
+            deviation(reference, result.value),
+        ]);
+    }
+
+    table
+}
+
+fn tabulate(options: &Options) -> Result<Table, String> {
+    let n_max = ((options.x_end - options.x_start) / options.x_step).ceil() as usize;
+    let points: Vec<f64> = (0..=n_max)
+        .map(|n| options.x_start + (n as f64) * options.x_step)
+        .collect();
+    let accuracy = options.accuracy;
+
+    Ok(match options.function.as_str() {
+        "sin" => tabulate_series(
+            accuracy,
+            &points,
+            ("sin", "\\sin"),
+            series::sin,
+            DoubleDouble::sin_trait,
+        ),
+        "cos" => tabulate_series(
+            accuracy,
+            &points,
+            ("cos", "\\cos"),
+            series::cos,
+            DoubleDouble::cos_trait,
+        ),
+        "exp" => tabulate_series(
+            accuracy,
+            &points,
+            ("exp", "\\exp"),
+            series::exp,
+            DoubleDouble::exp_trait,
+        ),
+        "ln1p" => tabulate_series(
+            accuracy,
+            &points,
+            ("ln1p", "\\ln(1+x)"),
+            series::ln_1p,
+            |x| (DoubleDouble::one() + *x).ln_trait(),
+        ),
+        "atan" => tabulate_series(
+            accuracy,
+            &points,
+            ("atan", "\\arctan"),
+            series::atan,
+            DoubleDouble::atan_trait,
+        ),
+        _ => tabulate_z(accuracy, &points)?,
+    })
+}
+
+fn main() {
+    let cli_args: Vec<String> = args().collect();
+
+    // a point outside of the domain of z is a usage error as well
+    let (options, table) = match parse_options(&cli_args[1..]).and_then(|options| {
+        let table = tabulate(&options)?;
+        Ok((options, table))
+    }) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            println!("{error}");
+            println!("usage: [command] {USAGE}");
+            exit(1);
+        }
+    };
+
+    table
+        .write(options.format, &mut stdout().lock())
+        .expect("failed to write the table");
 }
@@ -0,0 +1,267 @@
+use std::{
+    fmt::{self, Debug, Display},
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+// Floats the roots are computed for
+pub trait RootFloat:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Debug
+    + Display
+{
+    // exponents above this overflow
+    const MAX_EXPONENT: i32;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_u32(value: u32) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+    fn powi(self, n: i32) -> Self;
+    // 2^e
+    fn exp2i(e: i32) -> Self;
+    // x = m 2^e with m in [1, 2) for a positive finite x
+    fn split(self) -> (Self, i32);
+}
+
+macro_rules! impl_root_float {
+    ($ty:ident, $bits:ty, $mantissa_bits:expr, $bias:expr) => {
+        impl RootFloat for $ty {
+            const MAX_EXPONENT: i32 = $ty::MAX_EXP;
+
+            fn zero() -> Self {
+                0.
+            }
+            fn one() -> Self {
+                1.
+            }
+            fn from_u32(value: u32) -> Self {
+                value as $ty
+            }
+            fn is_finite(self) -> bool {
+                $ty::is_finite(self)
+            }
+            fn is_nan(self) -> bool {
+                $ty::is_nan(self)
+            }
+            fn powi(self, n: i32) -> Self {
+                $ty::powi(self, n)
+            }
+            fn exp2i(e: i32) -> Self {
+                // in two halves, so that the extreme exponents do not
+                // underflow on the way
+                (2. as $ty).powi(e / 2) * (2. as $ty).powi(e - e / 2)
+            }
+            fn split(self) -> (Self, i32) {
+                // subnormal numbers are scaled into the normal range first
+                if self < $ty::MIN_POSITIVE {
+                    let (m, e) = (self * Self::exp2i($mantissa_bits)).split();
+                    return (m, e - $mantissa_bits);
+                }
+
+                let bits = self.to_bits();
+                let exponent_mask =
+                    (1 << (std::mem::size_of::<$ty>() * 8 - 1 - $mantissa_bits)) - 1;
+                let e = ((bits >> $mantissa_bits) & exponent_mask) as i32 - $bias;
+                let m = $ty::from_bits(
+                    (bits & !(exponent_mask << $mantissa_bits))
+                        | (($bias as $bits) << $mantissa_bits),
+                );
+                (m, e)
+            }
+        }
+    };
+}
+
+impl_root_float!(f32, u32, 23, 127);
+impl_root_float!(f64, u64, 52, 1023);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootError {
+    ZeroDegree,
+    // the degree is larger than the exponent range of the float
+    DegreeTooLarge { degree: u32 },
+    EvenRootOfNegative,
+    ReciprocalOfZero,
+    NotFinite,
+    InvalidAccuracy,
+}
+
+impl Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootError::ZeroDegree => write!(f, "the degree of a root has to be positive"),
+            RootError::DegreeTooLarge { degree } => write!(f, "degree {degree} is too large"),
+            RootError::EvenRootOfNegative => write!(f, "even root of a negative number"),
+            RootError::ReciprocalOfZero => write!(f, "reciprocal square root of zero"),
+            RootError::NotFinite => write!(f, "the argument is not finite"),
+            RootError::InvalidAccuracy => write!(f, "accuracy has to be non negative"),
+        }
+    }
+}
+
+impl std::error::Error for RootError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Root<S> {
+    pub value: S,
+    // the root is within bound of the value, rounding errors are not
+    // included
+    pub bound: S,
+    pub iterations: usize,
+}
+
+impl<S> Root<S>
+where
+    S: RootFloat,
+{
+    fn exact(value: S) -> Self {
+        Root {
+            value,
+            bound: S::zero(),
+            iterations: 0,
+        }
+    }
+
+    fn scale(self, factor: S) -> Self {
+        Root {
+            value: self.value * factor,
+            bound: self.bound * factor,
+            ..self
+        }
+    }
+}
+
+fn check_accuracy<S>(acc: S) -> Result<(), RootError>
+where
+    S: RootFloat,
+{
+    if acc.is_nan() || acc < S::zero() {
+        Err(RootError::InvalidAccuracy)
+    } else {
+        Ok(())
+    }
+}
+
+// Newton's iteration for y^n = a with a in [1, 2^n) from y = 2. The iterates
+// decrease to the root and a / y^(n-1) stays under it, so the root is in
+// [a / y^(n-1), y] and the width of it is the bound.
+fn reduced_nth_root<S>(acc: S, a: S, n: u32) -> Root<S>
+where
+    S: RootFloat,
+{
+    let degree = S::from_u32(n);
+    let mut y = S::from_u32(2);
+    let mut iterations = 0;
+
+    loop {
+        let lower = a / y.powi(n as i32 - 1);
+        let bound = y - lower;
+        let next = ((degree - S::one()) * y + lower) / degree;
+
+        // rounding stops the iterates from decreasing at the last bits
+        if bound <= acc || next >= y {
+            return Root {
+                value: y,
+                bound,
+                iterations,
+            };
+        }
+
+        y = next;
+        iterations += 1;
+    }
+}
+
+// x^(1/n) with |x^(1/n) - value| <= acc, odd roots of negative x are negative
+pub fn nth_root<S>(acc: S, x: S, n: u32) -> Result<Root<S>, RootError>
+where
+    S: RootFloat,
+{
+    check_accuracy(acc)?;
+    if n == 0 {
+        return Err(RootError::ZeroDegree);
+    }
+    if n as i64 > S::MAX_EXPONENT as i64 {
+        return Err(RootError::DegreeTooLarge { degree: n });
+    }
+    if !x.is_finite() {
+        return Err(RootError::NotFinite);
+    }
+    if x < S::zero() {
+        if n.is_multiple_of(2) {
+            return Err(RootError::EvenRootOfNegative);
+        }
+        return nth_root(acc, -x, n).map(|root| root.scale(-S::one()));
+    }
+    if x == S::zero() || n == 1 {
+        return Ok(Root::exact(x));
+    }
+
+    // x = m 2^(nq + r) with 0 <= r < n, x^(1/n) = (m 2^r)^(1/n) 2^q
+    let (m, e) = x.split();
+    let (q, r) = (e.div_euclid(n as i32), e.rem_euclid(n as i32));
+    let scale = S::exp2i(q);
+
+    Ok(reduced_nth_root(acc / scale, m * S::exp2i(r), n).scale(scale))
+}
+
+pub fn sqrt<S>(acc: S, x: S) -> Result<Root<S>, RootError>
+where
+    S: RootFloat,
+{
+    nth_root(acc, x, 2)
+}
+
+// 1/sqrt(x) with |1/sqrt(x) - value| <= acc. For a in [1, 4) Newton's
+// iteration y (3 - a y^2) / 2 from y = 1/2 increases to the root and
+// 1/(a y) stays over it, so the root is in [y, 1/(a y)].
+pub fn rsqrt<S>(acc: S, x: S) -> Result<Root<S>, RootError>
+where
+    S: RootFloat,
+{
+    check_accuracy(acc)?;
+    if !x.is_finite() {
+        return Err(RootError::NotFinite);
+    }
+    if x < S::zero() {
+        return Err(RootError::EvenRootOfNegative);
+    }
+    if x == S::zero() {
+        return Err(RootError::ReciprocalOfZero);
+    }
+
+    // x = m 2^(2q + r), 1/sqrt(x) = 1/sqrt(m 2^r) 2^-q
+    let (m, e) = x.split();
+    let (q, r) = (e.div_euclid(2), e.rem_euclid(2));
+    let scale = S::exp2i(-q);
+    let a = m * S::exp2i(r);
+    let (two, three) = (S::from_u32(2), S::from_u32(3));
+
+    let mut y = S::one() / two;
+    let mut iterations = 0;
+    let root = loop {
+        let upper = S::one() / (a * y);
+        let bound = upper - y;
+        let next = y * (three - a * y * y) / two;
+
+        if bound <= acc / scale || next <= y {
+            break Root {
+                value: y,
+                bound,
+                iterations,
+            };
+        }
+
+        y = next;
+        iterations += 1;
+    };
+
+    Ok(root.scale(scale))
+}
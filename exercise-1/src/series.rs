@@ -0,0 +1,329 @@
+use std::f64::consts::{FRAC_PI_2, LN_2, SQRT_2};
+
+use exercise_2::{basic::Numerical, double_double::DoubleDouble};
+
+// pi/2 = PI_2_HIGH + PI_2_LOW with 33 bits in PI_2_HIGH, so k PI_2_HIGH is
+// exact for |k| < 2^20 and the reduced argument keeps the digits of
+// x - k pi/2 (Cody and Waite), larger arguments lose accuracy
+const PI_2_HIGH: f64 = 1.570_796_326_734_125_6;
+const PI_2_LOW: f64 = 6.077_100_506_506_192e-11;
+// ln(2) split the same way for the reduction of exp
+const LN_2_HIGH: f64 = 6.931_471_803_691_238e-1;
+const LN_2_LOW: f64 = 1.908_214_929_270_587_7e-10;
+
+// Scalars the series are summed in, f64 or DoubleDouble for reference
+// values. The argument reductions are done to the precision of the scalar,
+// the leading f64 part decides the branches.
+pub trait SeriesScalar: Numerical + From<f64> + Into<f64> {
+    // unit roundoff, truncations below it only add terms
+    const EPSILON: f64;
+
+    // x - k pi/2 and x - k ln(2) for an integer k
+    fn sub_half_pi(self, k: f64) -> Self;
+    fn sub_ln_2(self, k: f64) -> Self;
+    fn frac_pi_2() -> Self;
+    fn ln_2() -> Self;
+    // x 2^e, exact unless it overflows or is subnormal
+    fn scale_exp2(self, e: i32) -> Self;
+}
+
+impl SeriesScalar for f64 {
+    const EPSILON: f64 = f64::EPSILON;
+
+    fn sub_half_pi(self, k: f64) -> Self {
+        (self - k * PI_2_HIGH) - k * PI_2_LOW
+    }
+
+    fn sub_ln_2(self, k: f64) -> Self {
+        (self - k * LN_2_HIGH) - k * LN_2_LOW
+    }
+
+    fn frac_pi_2() -> Self {
+        FRAC_PI_2
+    }
+
+    fn ln_2() -> Self {
+        LN_2
+    }
+
+    fn scale_exp2(self, e: i32) -> Self {
+        // in two halves, 2^e alone overflows for the extreme exponents
+        self * 2f64.powi(e / 2) * 2f64.powi(e - e / 2)
+    }
+}
+
+impl SeriesScalar for DoubleDouble {
+    const EPSILON: f64 = DoubleDouble::EPSILON;
+
+    fn sub_half_pi(self, k: f64) -> Self {
+        DoubleDouble::sub_half_pi(&self, k)
+    }
+
+    fn sub_ln_2(self, k: f64) -> Self {
+        DoubleDouble::sub_ln_2(&self, k)
+    }
+
+    fn frac_pi_2() -> Self {
+        DoubleDouble::FRAC_PI_2
+    }
+
+    fn ln_2() -> Self {
+        DoubleDouble::LN_2
+    }
+
+    fn scale_exp2(self, e: i32) -> Self {
+        DoubleDouble::scale_exp2(&self, e)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SeriesResult<S = f64> {
+    pub value: S,
+    // number of summed terms of the series
+    pub terms: usize,
+    // guaranteed bound of the absolute truncation error of the value,
+    // rounding errors are not included
+    pub bound: S,
+}
+
+impl<S: SeriesScalar> SeriesResult<S> {
+    fn exact(value: f64) -> Self {
+        SeriesResult {
+            value: S::from(value),
+            terms: 0,
+            bound: S::zero(),
+        }
+    }
+
+    fn scale(self, factor: S) -> Self {
+        SeriesResult {
+            value: self.value * factor,
+            terms: self.terms,
+            bound: self.bound * factor.abs_trait(),
+        }
+    }
+
+    fn shift(self, offset: S) -> Self {
+        SeriesResult {
+            value: self.value + offset,
+            ..self
+        }
+    }
+}
+
+fn leading<S: SeriesScalar>(x: S) -> f64 {
+    x.into()
+}
+
+// x = k pi/2 + r with |r| <= pi/4, returns (r, k mod 4)
+fn reduce_half_pi<S: SeriesScalar>(x: S) -> (S, u8) {
+    let k = (leading(x) / FRAC_PI_2).round();
+    (x.sub_half_pi(k), k.rem_euclid(4.) as u8)
+}
+
+// x = m 2^e with m in [1/sqrt(2), sqrt(2)), x has to be positive and finite
+fn split_exponent<S: SeriesScalar>(x: S) -> (S, i32) {
+    // subnormal numbers are scaled into the normal range first
+    let (lead, offset) = if leading(x) < f64::MIN_POSITIVE {
+        (leading(x) * 2f64.powi(54), -54)
+    } else {
+        (leading(x), 0)
+    };
+
+    let bits = lead.to_bits();
+    let e = ((bits >> 52) & 0x7ff) as i32 - 1023 + offset;
+    let m = x.scale_exp2(-e);
+
+    if m >= S::from(SQRT_2) {
+        (m.scale_exp2(-1), e + 1)
+    } else {
+        (m, e)
+    }
+}
+
+fn integer<S: SeriesScalar>(value: usize) -> S {
+    S::from(value as i32)
+}
+
+// Sums the terms until the truncation bound of the next one is at most acc,
+// the first term is always taken. next(k, u) gives the term k from the term
+// k - 1, tail(k, u) bounds the sum of the terms from k on, u being the term k.
+fn sum_series<S: SeriesScalar>(
+    acc: S,
+    first: S,
+    next: impl Fn(usize, S) -> S,
+    tail: impl Fn(usize, S) -> S,
+) -> SeriesResult<S> {
+    assert!(
+        acc >= S::zero(),
+        "accuracy has to be non negative, got {acc:?}"
+    );
+
+    let mut r = first;
+    let mut k = 1;
+    let mut uk = next(k, first);
+
+    loop {
+        let bound = tail(k, uk);
+        if bound <= acc {
+            return SeriesResult {
+                value: r,
+                terms: k,
+                bound,
+            };
+        }
+
+        r = r + uk;
+        k += 1;
+        uk = next(k, uk);
+    }
+}
+
+// sin(r) for |r| <= pi/4, the terms alternate and decrease, so the tail is
+// bounded by the first omitted term
+fn sin_series<S: SeriesScalar>(acc: S, r: S) -> SeriesResult<S> {
+    sum_series(
+        acc,
+        r,
+        |k, u| -u * r * r / (integer::<S>(2 * k) * integer(2 * k + 1)),
+        |_, u| u.abs_trait(),
+    )
+}
+
+// cos(r) for |r| <= pi/4, the same as for sin
+fn cos_series<S: SeriesScalar>(acc: S, r: S) -> SeriesResult<S> {
+    sum_series(
+        acc,
+        S::one(),
+        |k, u| -u * r * r / (integer::<S>(2 * k - 1) * integer(2 * k)),
+        |_, u| u.abs_trait(),
+    )
+}
+
+fn sin_cos<S: SeriesScalar>(acc: S, x: S, quadrant_shift: u8) -> SeriesResult<S> {
+    if !leading(x).is_finite() {
+        return SeriesResult::exact(f64::NAN);
+    }
+
+    let (r, quadrant) = reduce_half_pi(x);
+    match (quadrant + quadrant_shift) % 4 {
+        0 => sin_series(acc, r),
+        1 => cos_series(acc, r),
+        2 => sin_series(acc, r).scale(-S::one()),
+        _ => cos_series(acc, r).scale(-S::one()),
+    }
+}
+
+pub fn sin<S: SeriesScalar>(acc: S, x: S) -> SeriesResult<S> {
+    sin_cos(acc, x, 0)
+}
+
+pub fn cos<S: SeriesScalar>(acc: S, x: S) -> SeriesResult<S> {
+    sin_cos(acc, x, 1)
+}
+
+// exp(x) = 2^k exp(r) with |r| <= ln(2)/2
+pub fn exp<S: SeriesScalar>(acc: S, x: S) -> SeriesResult<S> {
+    let lead = leading(x);
+    if lead.is_nan() {
+        return SeriesResult::exact(f64::NAN);
+    }
+    if lead > f64::MAX.ln() {
+        return SeriesResult::exact(f64::INFINITY);
+    }
+    if lead < f64::MIN_POSITIVE.ln() - 53. * LN_2 {
+        return SeriesResult::exact(0.);
+    }
+
+    let k = (lead / LN_2).round() as i32;
+    let r = x.sub_ln_2(k as f64);
+    // 2^k is applied in two halves, it overflows alone for k = 1024
+    let (first_scale, second_scale) = (S::from(2f64.powi(k / 2)), S::from(2f64.powi(k - k / 2)));
+    // exp(r) >= 0.7, a truncation under its rounding only adds terms
+    let series_acc = acc / first_scale / second_scale;
+    let floor = S::from(S::EPSILON / 4.);
+    let series_acc = if series_acc > floor {
+        series_acc
+    } else {
+        floor
+    };
+
+    // the terms after k are at most u (|r| / (k + 1))^j, a geometric tail
+    let series = sum_series(
+        series_acc,
+        S::one(),
+        |k, u| u * r / integer(k),
+        |k, u| u.abs_trait() / (S::one() - r.abs_trait() / integer(k + 1)),
+    );
+    series.scale(first_scale).scale(second_scale)
+}
+
+// ln(1 + x) = e ln(2) + ln(m) with 1 + x = m 2^e, ln(m) = 2 atanh(s) for
+// s = (m - 1) / (m + 1), |s| <= 0.172
+pub fn ln_1p<S: SeriesScalar>(acc: S, x: S) -> SeriesResult<S> {
+    let (lead, one) = (leading(x), S::one());
+    if lead.is_nan() || x < -one {
+        return SeriesResult::exact(f64::NAN);
+    }
+    if x == -one {
+        return SeriesResult::exact(f64::NEG_INFINITY);
+    }
+    if lead == f64::INFINITY {
+        return SeriesResult::exact(f64::INFINITY);
+    }
+
+    let (m, e) = split_exponent(one + x);
+    // without a shift of the exponent 1 + x is not rounded
+    let s = if e == 0 {
+        x / (integer::<S>(2) + x)
+    } else {
+        (m - one) / (m + one)
+    };
+
+    // the terms 2 s^(2k+1) / (2k+1) are positive with ratio below s^2
+    let series = sum_series(
+        acc,
+        integer::<S>(2) * s,
+        |k, u| u * s * s * integer(2 * k - 1) / integer(2 * k + 1),
+        |_, u| u.abs_trait() / (one - s * s),
+    );
+    series.shift(S::from(e) * S::ln_2())
+}
+
+// atan(x) with |x| > 1 reflected by atan(x) = pi/2 - atan(1/x), then halved
+// twice by atan(x) = 2 atan(x / (1 + sqrt(1 + x^2))) to |x| <= 0.199
+pub fn atan<S: SeriesScalar>(acc: S, x: S) -> SeriesResult<S> {
+    if leading(x).is_nan() {
+        return SeriesResult::exact(f64::NAN);
+    }
+
+    let one = S::one();
+    let (reflected, t) = if x.abs_trait() > one {
+        (true, one / x)
+    } else {
+        (false, x)
+    };
+    let halvings = 2;
+    let t = (0..halvings).fold(t, |t, _| t / (one + (one + t * t).sqrt_trait()));
+
+    // the terms alternate and decrease
+    let factor = integer(1 << halvings);
+    let series = sum_series(
+        acc / factor,
+        t,
+        |k, u| -u * t * t * integer(2 * k - 1) / integer(2 * k + 1),
+        |_, u| u.abs_trait(),
+    )
+    .scale(factor);
+
+    if reflected {
+        let half_pi = if x < S::zero() {
+            -S::frac_pi_2()
+        } else {
+            S::frac_pi_2()
+        };
+        series.scale(-one).shift(half_pi)
+    } else {
+        series
+    }
+}
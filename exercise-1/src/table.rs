@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Latex,
+    Markdown,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "csv" => Some(Format::Csv),
+            "latex" => Some(Format::Latex),
+            "markdown" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    // plain name for csv and markdown
+    pub name: String,
+    // math mode name for latex
+    pub latex: String,
+}
+
+impl Column {
+    pub fn new(name: &str, latex: &str) -> Self {
+        Column {
+            name: name.to_string(),
+            latex: latex.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Table {
+            columns,
+            rows: vec![],
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<f64>) {
+        assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    pub fn write(&self, format: Format, writer: &mut impl Write) -> io::Result<()> {
+        match format {
+            Format::Csv => self.write_csv(writer),
+            Format::Latex => self.write_latex(writer),
+            Format::Markdown => self.write_markdown(writer),
+        }
+    }
+
+    fn cells(row: &[f64]) -> Vec<String> {
+        row.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        writeln!(writer, "{}", names.join(","))?;
+        for row in self.rows.iter() {
+            writeln!(writer, "{}", Self::cells(row).join(","))?;
+        }
+        Ok(())
+    }
+
+    // a tabular environment to \input into a document
+    fn write_latex(&self, writer: &mut impl Write) -> io::Result<()> {
+        let alignment = vec!["c"; self.columns.len()].join("|");
+        let names: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| format!("${}$", c.latex))
+            .collect();
+
+        writeln!(writer, "\\begin{{tabular}}{{|{alignment}|}}")?;
+        writeln!(writer, "\\hline")?;
+        writeln!(writer, "{} \\\\", names.join(" & "))?;
+        writeln!(writer, "\\hline")?;
+        for row in self.rows.iter() {
+            writeln!(writer, "{} \\\\", Self::cells(row).join(" & "))?;
+        }
+        writeln!(writer, "\\hline")?;
+        writeln!(writer, "\\end{{tabular}}")
+    }
+
+    fn write_markdown(&self, writer: &mut impl Write) -> io::Result<()> {
+        let names: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        writeln!(writer, "| {} |", names.join(" | "))?;
+        writeln!(writer, "|{}", "---|".repeat(self.columns.len()))?;
+        for row in self.rows.iter() {
+            writeln!(writer, "| {} |", Self::cells(row).join(" | "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use exercise_1::{
+    bounded::{split_accuracy, Bounded},
+    roots::{nth_root, rsqrt, sqrt, RootError},
+    series::{self, SeriesResult},
+    table::{Column, Format, Table},
+};
+use exercise_2::random::Random;
+
+const CASES: u64 = 64;
+const POINTS: usize = 16;
+
+// Runs the property on CASES seeded inputs, POINTS random points each
+fn check(name: &str, property: impl Fn(&mut Random) -> Result<(), String>) {
+    for seed in 0..CASES {
+        if let Err(message) = property(&mut Random::new(seed)) {
+            panic!("{name} failed for seed={seed}: {message}");
+        }
+    }
+}
+
+// a random number with a random exponent in [2^min_exponent, 2^max_exponent)
+fn random_magnitude(random: &mut Random, min_exponent: i64, max_exponent: i64) -> f64 {
+    let exponent = random.integer(min_exponent, max_exponent) as i32;
+    // in two halves, 2^exponent alone is not representable for subnormals
+    random.uniform(1.0, 2.0) * 2f64.powi(exponent / 2) * 2f64.powi(exponent - exponent / 2)
+}
+
+// the series value against the f64 function, up to the truncation bound and
+// a few ulps of rounding in the reduction and the summation
+fn check_series(
+    name: &str,
+    result: SeriesResult,
+    x: f64,
+    expected: f64,
+    ulps: f64,
+) -> Result<(), String> {
+    let tolerance = result.bound + ulps * f64::EPSILON * expected.abs().max(1.0);
+    let error = (result.value - expected).abs();
+    if error > tolerance {
+        return Err(format!(
+            "{name}({x}) = {} misses {expected} by {error} > {tolerance} in {} terms",
+            result.value, result.terms
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn series_match_builtins_after_argument_reduction() {
+    const ACCURACY: f64 = 1e-14;
+
+    check("series_match_builtins_after_argument_reduction", |random| {
+        for _ in 0..POINTS {
+            // the reduction modulo pi/2 is exact up to |x| ~ 2^20
+            let x = random_magnitude(random, -30, 20) * random.uniform(-1.0, 1.0).signum();
+            check_series("sin", series::sin(ACCURACY, x), x, x.sin(), 4.0)?;
+            check_series("cos", series::cos(ACCURACY, x), x, x.cos(), 4.0)?;
+            check_series("atan", series::atan(ACCURACY, x), x, x.atan(), 4.0)?;
+
+            let x = random.uniform(-700.0, 700.0);
+            let result = series::exp(ACCURACY * x.exp(), x);
+            check_series("exp", result, x, x.exp(), 8.0)?;
+
+            let x = random_magnitude(random, -40, 40) - 1.0;
+            check_series("ln1p", series::ln_1p(ACCURACY, x), x, x.ln_1p(), 4.0)?;
+        }
+        Ok(())
+    });
+}
+
+// the reduction keeps the number of terms independent of |x|
+#[test]
+fn series_terms_do_not_grow_with_the_argument() {
+    let terms = |x: f64| {
+        [
+            series::sin(1e-15, x).terms,
+            series::cos(1e-15, x).terms,
+            series::atan(1e-15, x).terms,
+            series::exp(1e-15 * x.exp(), x).terms,
+            series::ln_1p(1e-15, x).terms,
+        ]
+    };
+    let small = terms(0.5);
+    for x in [10.0, 100.0, 700.0] {
+        for (small, large) in small.iter().zip(terms(x)) {
+            assert!(
+                large <= small + 2,
+                "{large} terms at {x} for {small} at 0.5"
+            );
+        }
+    }
+
+    assert!(series::sin(1e-15, f64::NAN).value.is_nan());
+    assert!(series::ln_1p(1e-15, -2.0).value.is_nan());
+    assert_eq!(series::ln_1p(1e-15, -1.0).value, f64::NEG_INFINITY);
+    assert_eq!(series::exp(1e-15, 1e3).value, f64::INFINITY);
+    assert_eq!(series::exp(1e-15, -1e3).value, 0.0);
+}
+
+// z = sin(x) / sqrt(y) as in the table, sources 0 and 1
+fn bounded_quotient(accuracies: &[f64], x: f64, y: f64) -> Bounded {
+    let u = Bounded::from_series(series::sin(accuracies[0], x), 0);
+    let root = sqrt(accuracies[1], y).unwrap();
+    u / Bounded::source(root.value, root.bound, 1)
+}
+
+#[test]
+fn split_accuracy_meets_the_target() {
+    check("split_accuracy_meets_the_target", |random| {
+        for _ in 0..POINTS {
+            let (x, y) = (random.uniform(-3.0, 3.0), random.uniform(0.01, 2.0));
+            let target = random_magnitude(random, -40, -4);
+            let evaluate = |accuracies: &[f64]| bounded_quotient(accuracies, x, y);
+
+            let accuracies = split_accuracy(target, 2, evaluate)
+                .ok_or_else(|| format!("no split for {target} at x={x} y={y}"))?;
+            let error = evaluate(&accuracies).error;
+            if error > target || accuracies.iter().any(|accuracy| *accuracy <= 0.0) {
+                return Err(format!("{accuracies:?} give {error} for {target}"));
+            }
+
+            let (u, v) = (x.sin(), y.sqrt());
+            let z = evaluate(&accuracies).value;
+            if (z - u / v).abs() > target + 8.0 * f64::EPSILON * (u / v).abs() {
+                return Err(format!("z = {z} misses {} by more than {target}", u / v));
+            }
+        }
+        Ok(())
+    });
+
+    // an input error alone above the target leaves nothing to split
+    let input = Bounded::with_error(1.0, 1e-3);
+    let split = split_accuracy(1e-4, 1, |accuracies| {
+        input.clone() + Bounded::source(2.0, accuracies[0], 0)
+    });
+    assert_eq!(split, None);
+}
+
+fn check_root(name: &str, value: f64, bound: f64, expected: f64) -> Result<(), String> {
+    let tolerance = bound + 4.0 * f64::EPSILON * expected.abs();
+    if (value - expected).abs() > tolerance {
+        return Err(format!(
+            "{name} = {value} misses {expected} by more than {tolerance}"
+        ));
+    }
+    Ok(())
+}
+
+// the iteration starts from the exponent, so the count stays small from
+// subnormal to huge x
+#[test]
+fn roots_match_builtins_from_the_exponent_seed() {
+    const MAX_ITERATIONS: usize = 8;
+    type Builtin = (u32, fn(f64) -> f64);
+
+    check("roots_match_builtins_from_the_exponent_seed", |random| {
+        for _ in 0..POINTS {
+            let x = random_magnitude(random, -1070, 1020);
+            let builtins: [Builtin; 2] = [(2, f64::sqrt), (3, f64::cbrt)];
+            for (degree, builtin) in builtins {
+                let accuracy = 1e-15 * builtin(x);
+                let root = nth_root(accuracy, x, degree).map_err(|error| error.to_string())?;
+                let name = format!("{x}^(1/{degree})");
+                check_root(&name, root.value, root.bound, builtin(x))?;
+                if root.iterations > MAX_ITERATIONS || root.bound > accuracy {
+                    return Err(format!("{name} took {} iterations", root.iterations));
+                }
+            }
+
+            let accuracy = 1e-15 / x.sqrt();
+            let root = rsqrt(accuracy, x).map_err(|error| error.to_string())?;
+            let name = format!("1/sqrt({x})");
+            check_root(&name, root.value, root.bound, 1.0 / x.sqrt())?;
+            if root.iterations > MAX_ITERATIONS || root.bound > accuracy {
+                return Err(format!("{name} took {} iterations", root.iterations));
+            }
+
+            // powf rounds 1/degree, the power of the root is checked instead
+            let x = random_magnitude(random, -1000, 1000);
+            let degree = random.integer(4, 16) as u32;
+            let root = nth_root(0.0, x, degree).map_err(|error| error.to_string())?;
+            let power = root.value.powi(degree as i32);
+            let tolerance = (degree as f64 + 1.0) * 2.0 * f64::EPSILON * x;
+            if (power - x).abs() > tolerance || root.iterations > 2 * MAX_ITERATIONS {
+                return Err(format!(
+                    "{x}^(1/{degree}) = {} gives {power} in {} iterations",
+                    root.value, root.iterations
+                ));
+            }
+        }
+        Ok(())
+    });
+
+    assert_eq!(nth_root(0.0, -8.0, 3).unwrap().value, -2.0);
+    assert_eq!(nth_root(0.0, 0.0, 2).unwrap().value, 0.0);
+    assert_eq!(nth_root::<f32>(0.0, 27.0, 3).unwrap().value, 3.0);
+    assert_eq!(nth_root(0.0, -4.0, 2), Err(RootError::EvenRootOfNegative));
+    assert_eq!(nth_root(0.0, 4.0, 0), Err(RootError::ZeroDegree));
+    assert_eq!(
+        nth_root(0.0, 4.0, 2000),
+        Err(RootError::DegreeTooLarge { degree: 2000 })
+    );
+    assert_eq!(nth_root(0.0, f64::INFINITY, 2), Err(RootError::NotFinite));
+    assert_eq!(nth_root(-1.0, 4.0, 2), Err(RootError::InvalidAccuracy));
+    assert_eq!(rsqrt(0.0, 0.0), Err(RootError::ReciprocalOfZero));
+    assert_eq!(rsqrt(0.0, -1.0), Err(RootError::EvenRootOfNegative));
+}
+
+fn written(table: &Table, format: Format) -> String {
+    let mut output = vec![];
+    table.write(format, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn tables_are_written_in_every_format() {
+    let mut table = Table::new(vec![Column::new("x", "x"), Column::new("du", "\\Delta_u")]);
+    table.push(vec![0.1, 1e-7]);
+    table.push(vec![-2.0, f64::NAN]);
+
+    assert_eq!(
+        written(&table, Format::Csv),
+        "x,du\n0.1,0.0000001\n-2,NaN\n"
+    );
+    assert_eq!(
+        written(&table, Format::Latex),
+        "\\begin{tabular}{|c|c|}\n\\hline\n$x$ & $\\Delta_u$ \\\\\n\\hline\n\
+         0.1 & 0.0000001 \\\\\n-2 & NaN \\\\\n\\hline\n\\end{tabular}\n"
+    );
+    assert_eq!(
+        written(&table, Format::Markdown),
+        "| x | du |\n|---|---|\n| 0.1 | 0.0000001 |\n| -2 | NaN |\n"
+    );
+
+    assert_eq!(Format::parse("markdown"), Some(Format::Markdown));
+    assert_eq!(Format::parse("tsv"), None);
+}
@@ -35,6 +35,45 @@ macro_rules! impl_other_numerical_ops {
 impl_other_numerical_ops!(f32);
 impl_other_numerical_ops!(f64);
 
+// Elementary functions for generic code, the names do not clash with the
+// inherent methods of floats
+pub trait ElementaryOps {
+    fn exp_trait(&self) -> Self;
+    fn ln_trait(&self) -> Self;
+    fn sin_trait(&self) -> Self;
+    fn cos_trait(&self) -> Self;
+    fn tan_trait(&self) -> Self;
+    fn atan_trait(&self) -> Self;
+}
+
+macro_rules! impl_elementary_ops {
+    ($ty:ident) => {
+        impl ElementaryOps for $ty {
+            fn exp_trait(&self) -> Self {
+                self.exp()
+            }
+            fn ln_trait(&self) -> Self {
+                self.ln()
+            }
+            fn sin_trait(&self) -> Self {
+                self.sin()
+            }
+            fn cos_trait(&self) -> Self {
+                self.cos()
+            }
+            fn tan_trait(&self) -> Self {
+                self.tan()
+            }
+            fn atan_trait(&self) -> Self {
+                self.atan()
+            }
+        }
+    };
+}
+
+impl_elementary_ops!(f32);
+impl_elementary_ops!(f64);
+
 pub trait Numerical
 where
     Self: Clone
@@ -0,0 +1,216 @@
+use std::time::{Duration, Instant};
+
+use exercise_2::{
+    basic::Index,
+    iterative_methods::{simple_iterative_solve, zeidel_iterative_solve},
+    matrix::{
+        column::{apply_at, ColumnFunc},
+        norms::NormedColumn,
+        traits::MatrixRef,
+    },
+    test::{create_generated_cases, Scalar, TestCase},
+};
+
+use crate::{solve_lu, solve_qr};
+
+pub const BENCH_USAGE: &str =
+    "bench [--sizes 8,16,32,64] [--repeats <r>] [--seed <s>] [--accuracy <e>]";
+
+#[derive(Debug, Clone)]
+struct BenchOptions {
+    sizes: Vec<Index>,
+    repeats: Index,
+    seed: u64,
+    accuracy: Scalar,
+}
+
+#[derive(Debug, Clone)]
+struct Measurement {
+    time: Duration,
+    flops: Scalar,
+    residual: Scalar,
+    error: Scalar,
+    iterations: Option<Index>,
+}
+
+fn parse_options(args: &[String]) -> Result<BenchOptions, String> {
+    let mut sizes = vec![8, 16, 32, 64];
+    let mut repeats = 3;
+    let mut seed = 1;
+    let mut accuracy: Scalar = 1e-8;
+
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {option}"))?;
+
+        match option.as_str() {
+            "--sizes" => {
+                sizes = value
+                    .split(',')
+                    .map(|size| match size.trim().parse::<Index>() {
+                        Ok(size) if size > 0 => Ok(size),
+                        _ => Err(format!("invalid size {size}")),
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "--repeats" => {
+                repeats = match value.parse::<Index>() {
+                    Ok(repeats) if repeats > 0 => repeats,
+                    _ => return Err(format!("invalid repeats {value}")),
+                }
+            }
+            "--seed" => {
+                seed = value.parse().map_err(|_| format!("invalid seed {value}"))?;
+            }
+            "--accuracy" => {
+                accuracy = value
+                    .parse()
+                    .map_err(|_| format!("invalid accuracy {value}"))?;
+                if accuracy.is_nan() || accuracy <= 0.0 {
+                    return Err(format!("accuracy has to be positive, got {value}"));
+                }
+            }
+            _ => return Err(format!("unknown option {option}")),
+        }
+    }
+
+    Ok(BenchOptions {
+        sizes,
+        repeats,
+        seed,
+        accuracy,
+    })
+}
+
+// the iterative methods have no step limit, so they are only run where
+// convergence is guaranteed without the normal equations: with a positive
+// dominant diagonal both |I - A/|A|_inf|_inf < 1 and the seidel matrix is a
+// contraction
+fn is_diagonally_dominant(case: &TestCase) -> bool {
+    let dimension = case.matrix.dimension();
+    (0..dimension).all(|i| {
+        let off_diagonal: Scalar = (0..dimension)
+            .filter(|j| *j != i)
+            .map(|j| case.matrix.at(i, j).abs())
+            .sum();
+        case.matrix.at(i, i) > off_diagonal
+    })
+}
+
+// best time over the repeats, the answer of the last run
+fn measure<T>(repeats: Index, mut run: impl FnMut() -> T) -> (Duration, T) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..repeats {
+        let start = Instant::now();
+        let value = run();
+        best = best.min(start.elapsed());
+        result = Some(value);
+    }
+    (best, result.unwrap())
+}
+
+fn measurement(
+    case: &TestCase,
+    time: Duration,
+    flops: Scalar,
+    answer: &Vec<Scalar>,
+    iterations: Option<Index>,
+) -> Measurement {
+    let dimension = case.matrix.dimension();
+    let residual = ColumnFunc::new(dimension, |i| {
+        apply_at(case.matrix.as_ref(), answer, i) - case.vector[i]
+    })
+    .norm_one()
+        / case.vector.norm_one();
+    let error = ColumnFunc::new(dimension, |i| answer[i] - case.answer[i]).norm_one()
+        / case.answer.norm_one();
+
+    Measurement {
+        time,
+        flops,
+        residual,
+        error,
+        iterations,
+    }
+}
+
+fn run_case(case: &TestCase, options: &BenchOptions) -> Vec<(&'static str, Measurement)> {
+    let n = case.matrix.dimension() as Scalar;
+    let repeats = options.repeats;
+    let mut measurements = vec![];
+
+    // factorization and one solve
+    let (time, answer) = measure(repeats, || solve_lu(&case.matrix, &case.vector));
+    let flops = 2.0 / 3.0 * n * n * n + 2.0 * n * n;
+    measurements.push(("lu", measurement(case, time, flops, &answer, None)));
+
+    let (time, answer) = measure(repeats, || solve_qr(&case.matrix, &case.vector));
+    let flops = 4.0 / 3.0 * n * n * n + 4.0 * n * n;
+    measurements.push(("qr", measurement(case, time, flops, &answer, None)));
+
+    if is_diagonally_dominant(case) {
+        // one matrix vector product per step, the setup is not counted
+        let (time, (answer, steps)) = measure(repeats, || {
+            simple_iterative_solve::<_, _, Vec<Scalar>>(
+                case.matrix.as_ref(),
+                &case.vector,
+                options.accuracy,
+            )
+        });
+        let flops = 2.0 * n * n * steps as Scalar;
+        measurements.push((
+            "simple",
+            measurement(case, time, flops, &answer, Some(steps)),
+        ));
+
+        let (time, (answer, steps)) = measure(repeats, || {
+            zeidel_iterative_solve::<_, _, Vec<Scalar>>(
+                case.matrix.as_ref(),
+                &case.vector,
+                options.accuracy,
+            )
+        });
+        let flops = 2.0 * n * n * steps as Scalar;
+        measurements.push((
+            "seidel",
+            measurement(case, time, flops, &answer, Some(steps)),
+        ));
+    }
+
+    measurements
+}
+
+pub fn bench_command(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args)?;
+
+    println!("family;n;param;method;time_ms;flops;gflops;residual;error;k");
+    for case in create_generated_cases(options.sizes.clone(), options.seed) {
+        for (method, result) in run_case(&case, &options) {
+            let Measurement {
+                time,
+                flops,
+                residual,
+                error,
+                iterations,
+            } = result;
+            let seconds = time.as_secs_f64();
+            let gflops = if seconds > 0.0 {
+                flops / seconds * 1e-9
+            } else {
+                Scalar::NAN
+            };
+            let iterations = iterations.map_or(String::new(), |k| k.to_string());
+
+            println!(
+                "{};{method};{:.6};{flops:.3e};{gflops:.3};{residual:.3e};{error:.3e};{iterations}",
+                case.name,
+                seconds * 1e3,
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,500 @@
+use std::{
+    cmp::Ordering,
+    f64::consts,
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::basic::{ElementaryOps, OtherNumericalOps};
+
+// Unevaluated sum hi + lo of two doubles with |lo| <= ulp(hi) / 2, about 32
+// significant digits. The arithmetic follows the QD library (Hida, Li and
+// Bailey), the relative error of an operation is a few EPSILON.
+//
+// The elementary functions reduce their argument with the double-double
+// constants, so they keep the precision for moderate arguments only.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+// the parts of pi/2 and ln(2) after DoubleDouble::FRAC_PI_2 and LN_2
+const FRAC_PI_2_THIRD: f64 = -1.4973849048591698e-33;
+const LN_2_THIRD: f64 = 5.707708438416212e-34;
+
+// a + b = s + e exactly
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let b_part = s - a;
+    (s, (a - (s - b_part)) + (b - b_part))
+}
+
+// the same for |a| >= |b|
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+// a b = p + e exactly
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl DoubleDouble {
+    pub const PI: DoubleDouble = DoubleDouble {
+        hi: consts::PI,
+        lo: 1.2246467991473532e-16,
+    };
+    pub const FRAC_PI_2: DoubleDouble = DoubleDouble {
+        hi: consts::FRAC_PI_2,
+        lo: 6.123233995736766e-17,
+    };
+    pub const LN_2: DoubleDouble = DoubleDouble {
+        hi: consts::LN_2,
+        lo: 2.3190468138462996e-17,
+    };
+    pub const E: DoubleDouble = DoubleDouble {
+        hi: consts::E,
+        lo: 1.4456468917292502e-16,
+    };
+
+    // 2^-104
+    pub const EPSILON: f64 = 4.930380657631324e-32;
+
+    const NAN: DoubleDouble = DoubleDouble {
+        hi: f64::NAN,
+        lo: f64::NAN,
+    };
+
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = two_sum(hi, lo);
+        Self::renormalized(hi, lo)
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.hi.is_finite()
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.hi.is_nan()
+    }
+
+    // self 2^e, exact unless the result overflows or is subnormal
+    pub fn scale_exp2(&self, e: i32) -> Self {
+        // in two halves, 2^e alone overflows for the extreme exponents
+        let (first, second) = (2f64.powi(e / 2), 2f64.powi(e - e / 2));
+        DoubleDouble {
+            hi: self.hi * first * second,
+            lo: self.lo * first * second,
+        }
+    }
+
+    // |hi| >= |lo| is assumed, an overflow gives an infinite hi
+    fn renormalized(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = quick_two_sum(hi, lo);
+        if hi.is_finite() {
+            DoubleDouble { hi, lo }
+        } else {
+            DoubleDouble { hi, lo: 0. }
+        }
+    }
+
+    fn mul_f64(&self, rhs: f64) -> Self {
+        let (p, e) = two_prod(self.hi, rhs);
+        Self::renormalized(p, e + self.lo * rhs)
+    }
+
+    fn powi(&self, n: u32) -> Self {
+        let (mut result, mut base, mut n) = (Self::one(), *self, n);
+        while n > 0 {
+            if n % 2 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n /= 2;
+        }
+        result
+    }
+
+    // self 10^e, in two halves for the same reason as scale_exp2
+    fn scale_pow10(&self, e: i32) -> Self {
+        let ten = DoubleDouble::from(10);
+        let half = e / 2;
+        [half, e - half].iter().fold(*self, |x, e| {
+            if *e >= 0 {
+                x * ten.powi(e.unsigned_abs())
+            } else {
+                x / ten.powi(e.unsigned_abs())
+            }
+        })
+    }
+
+    // self - k c for the constant c = parts[0] + parts[1] + parts[2], the
+    // products of k with the first two parts are exact, so the difference
+    // keeps its digits near the multiples of c
+    fn sub_multiple(&self, k: f64, parts: [f64; 3]) -> Self {
+        let difference = parts[..2].iter().fold(*self, |difference, part| {
+            let (p, e) = two_prod(*part, k);
+            difference - DoubleDouble::new(p, e)
+        });
+        difference - DoubleDouble::from(k * parts[2])
+    }
+
+    // self - k pi/2 for an integer k, the argument reduction of sin and cos
+    pub fn sub_half_pi(&self, k: f64) -> Self {
+        self.sub_multiple(k, [Self::FRAC_PI_2.hi, Self::FRAC_PI_2.lo, FRAC_PI_2_THIRD])
+    }
+
+    // self - k ln(2) for an integer k, the argument reduction of exp
+    pub fn sub_ln_2(&self, k: f64) -> Self {
+        self.sub_multiple(k, [Self::LN_2.hi, Self::LN_2.lo, LN_2_THIRD])
+    }
+
+    // x = k pi/2 + r, returns (r, k mod 4)
+    fn reduce_half_pi(&self) -> (Self, u8) {
+        let k = (self.hi / Self::FRAC_PI_2.hi).round();
+        (self.sub_half_pi(k), k.rem_euclid(4.) as u8)
+    }
+
+    // the terms of the Taylor series from first on, the next one is the last
+    // times factor(n) until it is below EPSILON relative to scale
+    fn taylor(first: Self, scale: f64, factor: impl Fn(i32) -> Self) -> Self {
+        let (mut sum, mut term, mut n) = (first, first, 1);
+        while term.hi.abs() > Self::EPSILON * scale {
+            term *= factor(n);
+            sum += term;
+            n += 1;
+        }
+        sum
+    }
+
+    // for |r| <= pi/4, where sin(r) >= 0.9 r
+    fn sin_taylor(r: Self) -> Self {
+        let square = r * r;
+        Self::taylor(r, 0.5 * r.hi.abs(), |n| {
+            -square / DoubleDouble::from((2 * n) * (2 * n + 1))
+        })
+    }
+
+    // for |r| <= pi/4, where cos(r) >= 0.7
+    fn cos_taylor(r: Self) -> Self {
+        let square = r * r;
+        Self::taylor(Self::one(), 0.5, |n| {
+            -square / DoubleDouble::from((2 * n - 1) * (2 * n))
+        })
+    }
+
+    fn sin_cos(&self, quadrant_shift: u8) -> Self {
+        if !self.is_finite() {
+            return Self::NAN;
+        }
+        let (r, quadrant) = self.reduce_half_pi();
+        match (quadrant + quadrant_shift) % 4 {
+            0 => Self::sin_taylor(r),
+            1 => Self::cos_taylor(r),
+            2 => -Self::sin_taylor(r),
+            _ => -Self::cos_taylor(r),
+        }
+    }
+}
+
+impl Display for DoubleDouble {
+    // scientific notation, the precision is the number of digits after the
+    // point, 31 by default
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_finite() || self.hi == 0. {
+            return write!(f, "{}", self.hi);
+        }
+
+        let digits = f.precision().unwrap_or(31);
+        let mut exponent = self.hi.abs().log10().floor() as i32;
+        let mut x = self.abs_trait().scale_pow10(-exponent);
+        // the estimate of the exponent can be off by one
+        if x.hi >= 10. {
+            x /= DoubleDouble::from(10);
+            exponent += 1;
+        } else if x.hi < 1. {
+            x *= DoubleDouble::from(10);
+            exponent -= 1;
+        }
+
+        // one more digit for the rounding
+        let mut decimal: Vec<u8> = Vec::with_capacity(digits + 2);
+        for _ in 0..digits + 2 {
+            let mut digit = x.hi.floor();
+            // hi may be rounded up to the next integer
+            if (x - DoubleDouble::from(digit)).hi < 0. {
+                digit -= 1.;
+            }
+            decimal.push(digit.clamp(0., 9.) as u8);
+            x = (x - DoubleDouble::from(digit)) * DoubleDouble::from(10);
+        }
+
+        if decimal.pop().is_some_and(|last| last >= 5) {
+            for position in (0..decimal.len()).rev() {
+                if decimal[position] < 9 {
+                    decimal[position] += 1;
+                    break;
+                }
+                decimal[position] = 0;
+                if position == 0 {
+                    decimal.insert(0, 1);
+                    decimal.pop();
+                    exponent += 1;
+                }
+            }
+        }
+
+        let text: String = decimal.iter().map(|digit| (b'0' + digit) as char).collect();
+        let sign = if self.hi < 0. { "-" } else { "" };
+        if digits == 0 {
+            write!(f, "{sign}{}e{exponent}", &text[..1])
+        } else {
+            write!(f, "{sign}{}.{}e{exponent}", &text[..1], &text[1..])
+        }
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.hi.partial_cmp(&other.hi)? {
+            Ordering::Equal => self.lo.partial_cmp(&other.lo),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl From<i32> for DoubleDouble {
+    fn from(value: i32) -> Self {
+        DoubleDouble::from(f64::from(value))
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(value: f64) -> Self {
+        DoubleDouble { hi: value, lo: 0. }
+    }
+}
+
+impl From<DoubleDouble> for f64 {
+    fn from(value: DoubleDouble) -> Self {
+        value.hi
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = DoubleDouble;
+
+    fn neg(self) -> Self::Output {
+        DoubleDouble {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = DoubleDouble;
+
+    fn add(self, rhs: DoubleDouble) -> Self::Output {
+        let (s, s_error) = two_sum(self.hi, rhs.hi);
+        let (t, t_error) = two_sum(self.lo, rhs.lo);
+        let (s, s_error) = quick_two_sum(s, s_error + t);
+        DoubleDouble::renormalized(s, s_error + t_error)
+    }
+}
+
+impl AddAssign for DoubleDouble {
+    fn add_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = DoubleDouble;
+
+    fn sub(self, rhs: DoubleDouble) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl SubAssign for DoubleDouble {
+    fn sub_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = DoubleDouble;
+
+    fn mul(self, rhs: DoubleDouble) -> Self::Output {
+        let (p, e) = two_prod(self.hi, rhs.hi);
+        DoubleDouble::renormalized(p, e + (self.hi * rhs.lo + self.lo * rhs.hi))
+    }
+}
+
+impl MulAssign for DoubleDouble {
+    fn mul_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = DoubleDouble;
+
+    // long division with three partial quotients
+    fn div(self, rhs: DoubleDouble) -> Self::Output {
+        let first = self.hi / rhs.hi;
+        if !first.is_finite() {
+            return DoubleDouble::from(first);
+        }
+        let remainder = self - rhs.mul_f64(first);
+        let second = remainder.hi / rhs.hi;
+        let remainder = remainder - rhs.mul_f64(second);
+        let third = remainder.hi / rhs.hi;
+        DoubleDouble::renormalized(first, second) + DoubleDouble::from(third)
+    }
+}
+
+impl DivAssign for DoubleDouble {
+    fn div_assign(&mut self, rhs: DoubleDouble) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for DoubleDouble {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(DoubleDouble::zero(), |sum, value| sum + value)
+    }
+}
+
+impl OtherNumericalOps for DoubleDouble {
+    fn abs_trait(&self) -> Self {
+        if self.hi < 0. {
+            -*self
+        } else {
+            *self
+        }
+    }
+
+    // one Newton step from the root of the leading part, negative numbers
+    // give NaN
+    fn sqrt_trait(&self) -> Self {
+        if self.hi == 0. {
+            return Self::zero();
+        }
+        if self.hi < 0. {
+            return Self::NAN;
+        }
+        if !self.is_finite() {
+            return DoubleDouble::from(self.hi.sqrt());
+        }
+
+        // x = m 4^e with m in [1, 4), the error of root^2 does not underflow
+        let e = (self.hi.log2().floor() as i32).div_euclid(2);
+        let m = self.scale_exp2(-2 * e);
+        let root = m.hi.sqrt();
+        let (square, square_error) = two_prod(root, root);
+        let remainder = m - DoubleDouble::new(square, square_error);
+        (DoubleDouble::from(root) + DoubleDouble::from(remainder.hi / (2. * root))).scale_exp2(e)
+    }
+
+    fn zero() -> Self {
+        DoubleDouble::from(0.)
+    }
+
+    fn one() -> Self {
+        DoubleDouble::from(1.)
+    }
+}
+
+impl ElementaryOps for DoubleDouble {
+    // exp(x) = 2^k (1 + t) with t = expm1(r) for r = (x - k ln(2)) / 2^9 from
+    // the series, then squared back by t = 2t + t^2
+    fn exp_trait(&self) -> Self {
+        const SQUARINGS: i32 = 9;
+        if self.is_nan() {
+            return *self;
+        }
+        if self.hi > f64::MAX.ln() {
+            return DoubleDouble::from(f64::INFINITY);
+        }
+        if self.hi < f64::MIN_POSITIVE.ln() - 53. * Self::LN_2.hi {
+            return Self::zero();
+        }
+
+        let k = (self.hi / Self::LN_2.hi).round();
+        let r = self.sub_ln_2(k).scale_exp2(-SQUARINGS);
+        // the squarings double the error of t each, so t is summed further
+        let mut t = Self::taylor(r, 2f64.powi(-SQUARINGS - 1), |n| {
+            r / DoubleDouble::from(n + 1)
+        });
+        for _ in 0..SQUARINGS {
+            t = t.scale_exp2(1) + t * t;
+        }
+        (Self::one() + t).scale_exp2(k as i32)
+    }
+
+    // one Newton step y + x exp(-y) - 1 from the logarithm of hi, the
+    // exponent is split off first so that exp(-y) stays in range
+    fn ln_trait(&self) -> Self {
+        if self.hi == 0. {
+            return DoubleDouble::from(f64::NEG_INFINITY);
+        }
+        if self.is_nan() || self.hi < 0. {
+            return Self::NAN;
+        }
+        if self.hi == f64::INFINITY {
+            return *self;
+        }
+
+        let e = self.hi.log2().floor() as i32;
+        let m = self.scale_exp2(-e);
+        let y = DoubleDouble::from(m.hi.ln());
+        let ln_m = y + m * (-y).exp_trait() - Self::one();
+        ln_m + Self::LN_2 * DoubleDouble::from(e)
+    }
+
+    fn sin_trait(&self) -> Self {
+        self.sin_cos(0)
+    }
+
+    fn cos_trait(&self) -> Self {
+        self.sin_cos(1)
+    }
+
+    fn tan_trait(&self) -> Self {
+        self.sin_trait() / self.cos_trait()
+    }
+
+    // |x| > 1 is reflected by atan(x) = pi/2 - atan(1/x), then one Newton
+    // step z + (x cos(z) - sin(z)) cos(z) for tan(z) = x from the atan of hi
+    fn atan_trait(&self) -> Self {
+        if self.is_nan() {
+            return *self;
+        }
+        if self.hi.abs() > 1. {
+            let half_pi = if self.hi > 0. {
+                Self::FRAC_PI_2
+            } else {
+                -Self::FRAC_PI_2
+            };
+            return half_pi - (Self::one() / *self).atan_trait();
+        }
+
+        let z = DoubleDouble::from(self.hi.atan());
+        let (sin, cos) = (z.sin_trait(), z.cos_trait());
+        z + (*self * cos - sin) * cos
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    cmp::Ordering,
+    iter::Sum,
+    ops::{Add, Div, DivAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::basic::{ElementaryOps, OtherNumericalOps};
+
+// Value of a function together with its partial derivatives by N variables.
+// Every operation applies the chain rule, so a function evaluated at
+// Gradient::variable(x, i) carries its exact derivative by x_i up to rounding.
+//
+// Comparisons look at the value only, the same branches are taken as for f64.
+#[derive(Clone, Copy, Debug)]
+pub struct Gradient<const N: usize> {
+    value: f64,
+    gradient: [f64; N],
+}
+
+// dual number value + derivative e with e^2 = 0
+pub type Dual = Gradient<1>;
+
+impl<const N: usize> Gradient<N> {
+    pub fn new(value: f64, gradient: [f64; N]) -> Self {
+        Self { value, gradient }
+    }
+
+    pub fn constant(value: f64) -> Self {
+        Self::new(value, [0.0; N])
+    }
+
+    // x_index = value
+    pub fn variable(value: f64, index: usize) -> Self {
+        let mut gradient = [0.0; N];
+        gradient[index] = 1.0;
+        Self::new(value, gradient)
+    }
+
+    // the variables x_i = values[i]
+    pub fn variables(values: &[f64; N]) -> [Self; N] {
+        std::array::from_fn(|index| Self::variable(values[index], index))
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn gradient(&self) -> [f64; N] {
+        self.gradient
+    }
+
+    pub fn partial(&self, index: usize) -> f64 {
+        self.gradient[index]
+    }
+
+    // g(self) for g(value) = value and g'(value) = derivative
+    fn chain(self, value: f64, derivative: f64) -> Self {
+        Self::new(value, self.gradient.map(|d| derivative * d))
+    }
+}
+
+impl Dual {
+    pub fn derivative(&self) -> f64 {
+        self.gradient[0]
+    }
+}
+
+impl<const N: usize> PartialEq for Gradient<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<const N: usize> PartialOrd for Gradient<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<const N: usize> From<i32> for Gradient<N> {
+    fn from(value: i32) -> Self {
+        Self::constant(value.into())
+    }
+}
+
+impl<const N: usize> From<f64> for Gradient<N> {
+    fn from(value: f64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl<const N: usize> Neg for Gradient<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.chain(-self.value, -1.0)
+    }
+}
+
+impl<const N: usize> Add for Gradient<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut gradient = self.gradient;
+        for (d, rhs_d) in gradient.iter_mut().zip(rhs.gradient) {
+            *d += rhs_d;
+        }
+        Self::new(self.value + rhs.value, gradient)
+    }
+}
+
+impl<const N: usize> Sub for Gradient<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const N: usize> SubAssign for Gradient<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: usize> Mul for Gradient<N> {
+    type Output = Self;
+
+    // (uv)' = u'v + uv'
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut gradient = self.gradient;
+        for (d, rhs_d) in gradient.iter_mut().zip(rhs.gradient) {
+            *d = *d * rhs.value + self.value * rhs_d;
+        }
+        Self::new(self.value * rhs.value, gradient)
+    }
+}
+
+impl<const N: usize> Div for Gradient<N> {
+    type Output = Self;
+
+    // (u/v)' = (u' - (u/v) v') / v
+    fn div(self, rhs: Self) -> Self::Output {
+        let value = self.value / rhs.value;
+        let mut gradient = self.gradient;
+        for (d, rhs_d) in gradient.iter_mut().zip(rhs.gradient) {
+            *d = (*d - value * rhs_d) / rhs.value;
+        }
+        Self::new(value, gradient)
+    }
+}
+
+impl<const N: usize> DivAssign for Gradient<N> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const N: usize> Sum for Gradient<N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::constant(0.0), |sum, value| sum + value)
+    }
+}
+
+impl<const N: usize> OtherNumericalOps for Gradient<N> {
+    // the derivative at zero is the one from the right
+    fn abs_trait(&self) -> Self {
+        if self.value < 0.0 {
+            -*self
+        } else {
+            *self
+        }
+    }
+
+    fn sqrt_trait(&self) -> Self {
+        let root = self.value.sqrt();
+        self.chain(root, 0.5 / root)
+    }
+
+    fn zero() -> Self {
+        Self::constant(0.0)
+    }
+
+    fn one() -> Self {
+        Self::constant(1.0)
+    }
+}
+
+impl<const N: usize> ElementaryOps for Gradient<N> {
+    fn exp_trait(&self) -> Self {
+        let exp = self.value.exp();
+        self.chain(exp, exp)
+    }
+
+    fn ln_trait(&self) -> Self {
+        self.chain(self.value.ln(), 1.0 / self.value)
+    }
+
+    fn sin_trait(&self) -> Self {
+        let (sin, cos) = self.value.sin_cos();
+        self.chain(sin, cos)
+    }
+
+    fn cos_trait(&self) -> Self {
+        let (sin, cos) = self.value.sin_cos();
+        self.chain(cos, -sin)
+    }
+
+    fn tan_trait(&self) -> Self {
+        let tan = self.value.tan();
+        self.chain(tan, 1.0 + tan * tan)
+    }
+
+    fn atan_trait(&self) -> Self {
+        self.chain(self.value.atan(), 1.0 / (1.0 + self.value * self.value))
+    }
+}
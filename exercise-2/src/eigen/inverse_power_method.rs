@@ -0,0 +1,133 @@
+use crate::{
+    basic::{Numerical, OtherNumericalOps},
+    lu_decomposition::LUDecomposition,
+    matrix::{
+        column::{apply, dot, ColumnFuncInitializer, ColumnRef},
+        dense::DenseRowMatrix,
+        traits::{MatrixFuncInitializer, MatrixRef},
+    },
+};
+
+use super::{max, norm_one, EigenOptions, EigenPair};
+
+fn rayleigh_quotient<Matrix>(matrix: &Matrix, vector: &Vec<Matrix::Scalar>) -> Matrix::Scalar
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+{
+    let product: Vec<Matrix::Scalar> = apply(matrix, vector);
+    dot(vector, &product) / dot(vector, vector)
+}
+
+// An eigenvalue near the initial shift and its eigenvector, the shift is
+// corrected by the Rayleigh like estimate every step until the shifted matrix
+// becomes too close to singular, so the closest eigenvalue is not
+// guaranteed. None if the method did not converge in
+// max_iterations.
+pub fn inverse_power_method<Matrix, ColumnOut>(
+    matrix: &Matrix,
+    initial: Matrix::Scalar,
+    options: &EigenOptions<Matrix::Scalar>,
+) -> Option<EigenPair<Matrix::Scalar, ColumnOut>>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
+{
+    let dimension = matrix.dimension();
+    let zero = Matrix::Scalar::zero();
+    let one = Matrix::Scalar::one();
+
+    let mut y_vector = vec![zero; dimension];
+    y_vector[0] = one;
+    let mut shift = initial;
+    let mut decomposition: Option<LUDecomposition<DenseRowMatrix<Matrix::Scalar>>> = None;
+    let mut stop_shifting = false;
+
+    for _ in 0..options.max_iterations {
+        let z_vector = y_vector.clone();
+
+        // (A - sE) y = z
+        if !stop_shifting {
+            let shifted =
+                LUDecomposition::calculate(DenseRowMatrix::new_func(dimension, |row, column| {
+                    if row == column {
+                        matrix.at(row, column) - shift
+                    } else {
+                        matrix.at(row, column)
+                    }
+                }));
+
+            let min_pivot = shifted.min_pivot();
+            if decomposition.is_none() && min_pivot == zero {
+                // the initial shift is exactly an eigenvalue, it is moved
+                // away to get a solvable system
+                shift = shift + options.min_pivot * max(shift.abs_trait(), one);
+                continue;
+            }
+
+            if min_pivot < options.min_pivot {
+                stop_shifting = true;
+                // a shift this close to an eigenvalue is still usable for the
+                // first solve
+                if decomposition.is_none() {
+                    decomposition = Some(shifted);
+                }
+            } else {
+                decomposition = Some(shifted);
+            }
+        }
+
+        // the first step either stores a decomposition or continues
+        let decomposition = decomposition.as_ref().unwrap();
+        decomposition.solve(&mut y_vector);
+
+        // mu estimates the distance from the shift to the eigenvalue, for an
+        // eigenvector z = (lambda - s) y
+        let mu = if stop_shifting {
+            zero
+        } else {
+            dot(&z_vector, &y_vector) / dot(&y_vector, &y_vector)
+        };
+
+        let norm = norm_one(&y_vector);
+        for y in y_vector.iter_mut() {
+            *y /= norm;
+        }
+
+        // the vector may flip its sign every step
+        let sign = if dot(&y_vector, &z_vector) > zero {
+            one
+        } else {
+            -one
+        };
+        let difference_norm: Matrix::Scalar = y_vector
+            .iter()
+            .zip(&z_vector)
+            .map(|(y, z)| (sign * *y - *z).abs_trait())
+            .sum();
+
+        let vector_converged =
+            difference_norm < options.accuracy * max(norm_one(&z_vector), norm_one(&y_vector));
+        let shift_converged = stop_shifting
+            || mu.abs_trait() < options.accuracy * max(shift.abs_trait(), (shift + mu).abs_trait());
+
+        if vector_converged && shift_converged {
+            // the last shift is off by up to min_pivot, the Rayleigh quotient
+            // of the converged vector is not
+            let value = if stop_shifting {
+                rayleigh_quotient(matrix, &y_vector)
+            } else {
+                shift + mu
+            };
+            return Some(EigenPair {
+                value,
+                vector: ColumnOut::new_func(dimension, |i| y_vector[i]),
+            });
+        }
+
+        shift = shift + mu;
+    }
+
+    None
+}
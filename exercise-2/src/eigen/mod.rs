@@ -0,0 +1,59 @@
+use crate::basic::{Index, Numerical};
+
+pub mod inverse_power_method;
+pub mod power_method;
+pub mod qr_algorithm;
+
+#[derive(Clone, Debug)]
+pub struct EigenOptions<Scalar> {
+    // relative change of the eigenvalue and the eigenvector to stop at
+    pub accuracy: Scalar,
+    // coordinates of the eigenvector smaller than this do not estimate the
+    // eigenvalue
+    pub min_coordinate: Scalar,
+    // the inverse power method stops shifting when the pivot gets smaller
+    pub min_pivot: Scalar,
+    // the QR algorithm deflates when the last subdiagonal element gets smaller
+    pub deflation: Scalar,
+    pub max_iterations: Index,
+}
+
+impl<Scalar> Default for EigenOptions<Scalar>
+where
+    Scalar: Numerical,
+{
+    fn default() -> Self {
+        Self {
+            accuracy: Scalar::one() / Scalar::from(1_000_000),
+            min_coordinate: Scalar::one() / Scalar::from(100_000_000),
+            min_pivot: Scalar::one() / Scalar::from(10_000_000),
+            deflation: Scalar::one() / Scalar::from(100_000_000),
+            max_iterations: 1_000_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EigenPair<Scalar, Column> {
+    pub value: Scalar,
+    // normed in the first norm
+    pub vector: Column,
+}
+
+fn norm_one<Scalar>(values: &[Scalar]) -> Scalar
+where
+    Scalar: Numerical,
+{
+    values.iter().map(|value| value.abs_trait()).sum()
+}
+
+fn max<Scalar>(first: Scalar, second: Scalar) -> Scalar
+where
+    Scalar: Numerical,
+{
+    if first > second {
+        first
+    } else {
+        second
+    }
+}
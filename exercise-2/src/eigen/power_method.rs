@@ -0,0 +1,71 @@
+use crate::{
+    basic::{Numerical, OtherNumericalOps},
+    matrix::{
+        column::{apply, ColumnFuncInitializer, ColumnRef},
+        traits::MatrixRef,
+    },
+};
+
+use super::{max, norm_one, EigenOptions, EigenPair};
+
+// Eigenvalue of the largest absolute value and its eigenvector, None if the
+// estimates did not settle in max_iterations
+pub fn power_method<Matrix, ColumnOut>(
+    matrix: &Matrix,
+    options: &EigenOptions<Matrix::Scalar>,
+) -> Option<EigenPair<Matrix::Scalar, ColumnOut>>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
+{
+    let dimension = matrix.dimension();
+    let zero = Matrix::Scalar::zero();
+
+    let mut y_vector = vec![zero; dimension];
+    y_vector[0] = Matrix::Scalar::one();
+
+    // lambda_i = y_i / z_i, only coordinates that are not too small are valid
+    let mut current_lambda: Vec<Option<Matrix::Scalar>> = vec![None; dimension];
+
+    for _ in 0..options.max_iterations {
+        let norm = norm_one(&y_vector);
+        let z_vector: Vec<Matrix::Scalar> = y_vector.iter().map(|y| *y / norm).collect();
+        y_vector = apply(matrix, &z_vector);
+
+        let previous_lambda = current_lambda;
+        current_lambda = y_vector
+            .iter()
+            .zip(&z_vector)
+            .map(|(y, z)| (z.abs_trait() >= options.min_coordinate).then(|| *y / *z))
+            .collect();
+
+        let lambda_norm = |lambda: &[Option<Matrix::Scalar>]| {
+            lambda.iter().flatten().map(|l| l.abs_trait()).sum()
+        };
+        let difference_norm: Matrix::Scalar = previous_lambda
+            .iter()
+            .zip(&current_lambda)
+            .filter(|(previous, current)| previous.is_some() || current.is_some())
+            .map(|(previous, current)| {
+                (previous.unwrap_or(zero) - current.unwrap_or(zero)).abs_trait()
+            })
+            .sum();
+
+        if difference_norm
+            < options.accuracy * max(lambda_norm(&previous_lambda), lambda_norm(&current_lambda))
+        {
+            let valid: Vec<Matrix::Scalar> = current_lambda.iter().flatten().copied().collect();
+            let value = valid.iter().copied().sum::<Matrix::Scalar>()
+                / Matrix::Scalar::from(valid.len() as i32);
+
+            let norm = norm_one(&y_vector);
+            return Some(EigenPair {
+                value,
+                vector: ColumnOut::new_func(dimension, |i| y_vector[i] / norm),
+            });
+        }
+    }
+
+    None
+}
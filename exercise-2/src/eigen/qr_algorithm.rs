@@ -0,0 +1,204 @@
+use std::cmp::Ordering;
+
+use crate::{
+    basic::{Index, Numerical, OtherNumericalOps},
+    matrix::{
+        column::{ColumnFuncInitializer, ColumnRef},
+        dense::DenseRowMatrix,
+        traits::{MatrixFuncInitializer, MatrixMutRef, MatrixRef},
+    },
+};
+
+use super::{inverse_power_method::inverse_power_method, EigenOptions, EigenPair};
+
+// Householder direction v with (E - 2vv^T/(v^T v)) x = length e_1, returns
+// (length, v^T v). The length has the opposite sign of x_0 so that v_0 does
+// not cancel, v^T v is zero only for a zero x.
+fn compute_reflection<Scalar>(vector: &[Scalar], direction: &mut [Scalar]) -> (Scalar, Scalar)
+where
+    Scalar: Numerical,
+{
+    let length = vector.iter().map(|x| *x * *x).sum::<Scalar>().sqrt_trait();
+    let length = if vector[0] > Scalar::zero() {
+        -length
+    } else {
+        length
+    };
+
+    direction.copy_from_slice(vector);
+    direction[0] = vector[0] - length;
+    let scale = direction.iter().map(|v| *v * *v).sum();
+
+    (length, scale)
+}
+
+// reduces the matrix to upper Hessenberg form by similar reflections
+pub fn hessenberg_form<Matrix>(matrix: &mut Matrix)
+where
+    Matrix: MatrixMutRef,
+    Matrix::Scalar: Numerical,
+{
+    let dimension = matrix.dimension();
+    if dimension < 3 {
+        return;
+    }
+
+    let two = Matrix::Scalar::from(2);
+    let mut direction = vec![Matrix::Scalar::zero(); dimension];
+    let mut working_column = vec![Matrix::Scalar::zero(); dimension];
+
+    for col in 0..dimension - 2 {
+        for (k, value) in working_column.iter_mut().enumerate().skip(col + 1) {
+            *value = matrix.at(k, col);
+        }
+        let (length, scale) = compute_reflection(
+            &working_column[col + 1..dimension],
+            &mut direction[col + 1..dimension],
+        );
+        if scale == Matrix::Scalar::zero() {
+            continue;
+        }
+
+        // A <- HA, the reflected column is known
+        *matrix.at_mut(col + 1, col) = length;
+        for r in col + 2..dimension {
+            *matrix.at_mut(r, col) = Matrix::Scalar::zero();
+        }
+        for c in col + 1..dimension {
+            let dot_product: Matrix::Scalar = (col + 1..dimension)
+                .map(|k| direction[k] * matrix.at(k, c))
+                .sum();
+            let coefficient = -two * dot_product / scale;
+            for (r, v) in direction.iter().enumerate().skip(col + 1) {
+                *matrix.at_mut(r, c) = matrix.at(r, c) + coefficient * *v;
+            }
+        }
+
+        // A <- AH
+        for r in 0..dimension {
+            let dot_product: Matrix::Scalar = (col + 1..dimension)
+                .map(|k| direction[k] * matrix.at(r, k))
+                .sum();
+            let coefficient = -two * dot_product / scale;
+            for (c, v) in direction.iter().enumerate().skip(col + 1) {
+                *matrix.at_mut(r, c) = matrix.at(r, c) + coefficient * *v;
+            }
+        }
+    }
+}
+
+// A=QR -> RQ on the leading active x active block of a Hessenberg matrix
+fn qr_iteration<Matrix>(matrix: &mut Matrix, active: Index)
+where
+    Matrix: MatrixMutRef,
+    Matrix::Scalar: Numerical,
+{
+    let zero = Matrix::Scalar::zero();
+    let two = Matrix::Scalar::from(2);
+    let mut reflections = vec![zero; 2 * (active - 1)];
+    let mut scales = vec![zero; active - 1];
+
+    // A <- R
+    for col in 0..active - 1 {
+        let (length, scale) = compute_reflection(
+            &[matrix.at(col, col), matrix.at(col + 1, col)],
+            &mut reflections[col * 2..col * 2 + 2],
+        );
+        scales[col] = scale;
+        if scale == zero {
+            continue;
+        }
+
+        let reflection = &reflections[col * 2..col * 2 + 2];
+        *matrix.at_mut(col, col) = length;
+        *matrix.at_mut(col + 1, col) = zero;
+        for c in col + 1..active {
+            let coefficient = -two
+                * (reflection[0] * matrix.at(col, c) + reflection[1] * matrix.at(col + 1, c))
+                / scale;
+            *matrix.at_mut(col, c) = matrix.at(col, c) + coefficient * reflection[0];
+            *matrix.at_mut(col + 1, c) = matrix.at(col + 1, c) + coefficient * reflection[1];
+        }
+    }
+
+    // A <- AQ
+    for k in 0..active - 1 {
+        let scale = scales[k];
+        if scale == zero {
+            continue;
+        }
+
+        let reflection = &reflections[k * 2..k * 2 + 2];
+        for r in 0..active {
+            let coefficient = -two
+                * (reflection[0] * matrix.at(r, k) + reflection[1] * matrix.at(r, k + 1))
+                / scale;
+            *matrix.at_mut(r, k) = matrix.at(r, k) + coefficient * reflection[0];
+            *matrix.at_mut(r, k + 1) = matrix.at(r, k + 1) + coefficient * reflection[1];
+        }
+    }
+}
+
+// Eigenvalues in ascending order by the shifted QR algorithm with deflation,
+// the eigenvalues have to be real. None if the subdiagonal did not vanish in
+// max_iterations.
+pub fn qr_algorithm<Matrix, ColumnOut>(
+    matrix: &Matrix,
+    options: &EigenOptions<Matrix::Scalar>,
+) -> Option<ColumnOut>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
+{
+    let dimension = matrix.dimension();
+    let mut work_matrix: DenseRowMatrix<Matrix::Scalar> = DenseRowMatrix::from_matrix(matrix);
+    let mut values = Vec::with_capacity(dimension);
+    let mut active = dimension;
+
+    hessenberg_form(&mut work_matrix);
+
+    let mut iterations = 0;
+    while active > 1 {
+        if iterations == options.max_iterations {
+            return None;
+        }
+        iterations += 1;
+
+        let shift = work_matrix.at(active - 1, active - 1);
+        for k in 0..active {
+            *work_matrix.at_mut(k, k) -= shift;
+        }
+        qr_iteration(&mut work_matrix, active);
+        for k in 0..active {
+            *work_matrix.at_mut(k, k) = work_matrix.at(k, k) + shift;
+        }
+
+        if work_matrix.at(active - 1, active - 2).abs_trait() < options.deflation {
+            values.push(work_matrix.at(active - 1, active - 1));
+            active -= 1;
+        }
+    }
+    values.push(work_matrix.at(0, 0));
+
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Some(ColumnOut::new_func(dimension, |i| values[i]))
+}
+
+// Eigenvalues by the QR algorithm, each refined together with its
+// eigenvector by the inverse power method
+pub fn eigen_pairs<Matrix, ColumnOut>(
+    matrix: &Matrix,
+    options: &EigenOptions<Matrix::Scalar>,
+) -> Option<Vec<EigenPair<Matrix::Scalar, ColumnOut>>>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
+{
+    let values: Vec<Matrix::Scalar> = qr_algorithm(matrix, options)?;
+    values
+        .into_iter()
+        .map(|value| inverse_power_method(matrix, value, options))
+        .collect()
+}
@@ -0,0 +1,398 @@
+use std::{
+    cmp::Ordering,
+    f64::consts::{FRAC_PI_2, PI},
+    fmt::{self, Display},
+    iter::Sum,
+    ops::{Add, Div, DivAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use crate::basic::{ElementaryOps, OtherNumericalOps};
+
+// the std elementary functions are not correctly rounded, their results are
+// widened by this many ulps
+const LIBM_ULPS: usize = 2;
+
+// the error of products, quotients and roots this small may be lost to
+// underflow, they are widened both ways
+const TINY: f64 = f64::MIN_POSITIVE * (1u64 << 53) as f64;
+
+// Closed interval [lo, hi] enclosing a real number. The arithmetic rounds
+// outwards, the result contains the exact result for every choice of the
+// operands in their intervals.
+//
+// Comparisons order by the midpoint and then by the radius, they choose
+// pivots but prove nothing, certainly_positive and the like do. They are
+// Equal only for equal bounds, as ==.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    pub const ENTIRE: Interval = Interval {
+        lo: f64::NEG_INFINITY,
+        hi: f64::INFINITY,
+    };
+
+    const NAN: Interval = Interval {
+        lo: f64::NAN,
+        hi: f64::NAN,
+    };
+
+    // NaN bounds or lo > hi give the NaN interval, which contains nothing
+    pub fn new(lo: f64, hi: f64) -> Self {
+        if lo <= hi {
+            Self { lo, hi }
+        } else {
+            Interval::NAN
+        }
+    }
+
+    pub fn point(value: f64) -> Self {
+        Self::new(value, value)
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn midpoint(&self) -> f64 {
+        0.5 * self.lo + 0.5 * self.hi
+    }
+
+    pub fn radius(&self) -> f64 {
+        (0.5 * self.hi - 0.5 * self.lo).next_up()
+    }
+
+    pub fn width(&self) -> f64 {
+        (self.hi - self.lo).next_up()
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    pub fn contains_zero(&self) -> bool {
+        self.contains(0.)
+    }
+
+    pub fn is_subset(&self, other: &Interval) -> bool {
+        other.lo <= self.lo && self.hi <= other.hi
+    }
+
+    pub fn hull(&self, other: &Interval) -> Interval {
+        Interval::new(self.lo.min(other.lo), self.hi.max(other.hi))
+    }
+
+    pub fn certainly_positive(&self) -> bool {
+        self.lo > 0.
+    }
+
+    pub fn certainly_negative(&self) -> bool {
+        self.hi < 0.
+    }
+
+    // bounds computed by the library functions, widened for their error
+    fn widened(lo: f64, hi: f64) -> Interval {
+        let lo = (0..LIBM_ULPS).fold(lo, |lo, _| lo.next_down());
+        let hi = (0..LIBM_ULPS).fold(hi, |hi, _| hi.next_up());
+        Interval { lo, hi }
+    }
+}
+
+// Rounded result of an operation with the exact error (exact - result), NaN
+// if it is not known. Returns the bounds of the exact result.
+fn directed(result: f64, error: f64) -> (f64, f64) {
+    if error.is_nan() {
+        (result.next_down(), result.next_up())
+    } else if error > 0. {
+        (result, result.next_up())
+    } else if error < 0. {
+        (result.next_down(), result)
+    } else {
+        (result, result)
+    }
+}
+
+fn unless_tiny(value: f64, error: f64) -> f64 {
+    if value.abs() < TINY {
+        f64::NAN
+    } else {
+        error
+    }
+}
+
+fn sum_bounds(a: f64, b: f64) -> (f64, f64) {
+    // TwoSum, the error is exact whenever the sum does not overflow
+    let sum = a + b;
+    let b_part = sum - a;
+    let error = (a - (sum - b_part)) + (b - b_part);
+    directed(sum, error)
+}
+
+fn product_bounds(a: f64, b: f64) -> (f64, f64) {
+    // a zero bound of an unbounded interval gives zero
+    if a == 0. || b == 0. {
+        return (0., 0.);
+    }
+    let product = a * b;
+    directed(product, unless_tiny(product, a.mul_add(b, -product)))
+}
+
+fn quotient_bounds(a: f64, b: f64) -> (f64, f64) {
+    if a == 0. {
+        return (0., 0.);
+    }
+    let quotient = a / b;
+    // a/b - q = (a - q b) / b
+    let remainder = -quotient.mul_add(b, -a);
+    directed(quotient, unless_tiny(quotient, remainder * b.signum()))
+}
+
+// the smallest lower and the largest upper bound of the candidates
+fn hull_of(candidates: [(f64, f64); 4]) -> Interval {
+    if candidates.iter().any(|(lo, hi)| lo.is_nan() || hi.is_nan()) {
+        return Interval::ENTIRE;
+    }
+    candidates.iter().fold(
+        Interval {
+            lo: f64::INFINITY,
+            hi: f64::NEG_INFINITY,
+        },
+        |hull, (lo, hi)| Interval {
+            lo: hull.lo.min(*lo),
+            hi: hull.hi.max(*hi),
+        },
+    )
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // the radius rounds, for the same midpoint a lower lo is a larger
+        // radius and then hi tells the rest
+        match self.midpoint().partial_cmp(&other.midpoint())? {
+            Ordering::Equal => match other.lo.partial_cmp(&self.lo)? {
+                Ordering::Equal => self.hi.partial_cmp(&other.hi),
+                ordering => Some(ordering),
+            },
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl From<i32> for Interval {
+    fn from(value: i32) -> Self {
+        Interval::point(f64::from(value))
+    }
+}
+
+impl From<f64> for Interval {
+    fn from(value: f64) -> Self {
+        Interval::point(value)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Self::Output {
+        Interval {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, rhs: Interval) -> Self::Output {
+        Interval {
+            lo: sum_bounds(self.lo, rhs.lo).0,
+            hi: sum_bounds(self.hi, rhs.hi).1,
+        }
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, rhs: Interval) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl SubAssign for Interval {
+    fn sub_assign(&mut self, rhs: Interval) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, rhs: Interval) -> Self::Output {
+        hull_of([
+            product_bounds(self.lo, rhs.lo),
+            product_bounds(self.lo, rhs.hi),
+            product_bounds(self.hi, rhs.lo),
+            product_bounds(self.hi, rhs.hi),
+        ])
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+
+    // a divisor containing zero gives the entire line
+    fn div(self, rhs: Interval) -> Self::Output {
+        if rhs.contains_zero() {
+            return Interval::ENTIRE;
+        }
+        hull_of([
+            quotient_bounds(self.lo, rhs.lo),
+            quotient_bounds(self.lo, rhs.hi),
+            quotient_bounds(self.hi, rhs.lo),
+            quotient_bounds(self.hi, rhs.hi),
+        ])
+    }
+}
+
+impl DivAssign for Interval {
+    fn div_assign(&mut self, rhs: Interval) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for Interval {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Interval::zero(), |sum, value| sum + value)
+    }
+}
+
+impl OtherNumericalOps for Interval {
+    fn abs_trait(&self) -> Self {
+        if self.lo >= 0. {
+            *self
+        } else if self.hi <= 0. {
+            -*self
+        } else {
+            Interval {
+                lo: 0.,
+                hi: self.hi.max(-self.lo),
+            }
+        }
+    }
+
+    // negative parts are cut off, an entirely negative interval gives NaN
+    fn sqrt_trait(&self) -> Self {
+        if self.hi < 0. {
+            return Interval::NAN;
+        }
+        let bounds = |x: f64| {
+            if x == 0. {
+                return (0., 0.);
+            }
+            let root = x.sqrt();
+            // x - root^2 is exact
+            directed(root, unless_tiny(x, -root.mul_add(root, -x)))
+        };
+        Interval {
+            lo: bounds(self.lo.max(0.)).0,
+            hi: bounds(self.hi).1,
+        }
+    }
+
+    fn zero() -> Self {
+        Interval::point(0.)
+    }
+
+    fn one() -> Self {
+        Interval::point(1.)
+    }
+}
+
+// whether offset + k period lies in [lo, hi] for an integer k, decided with
+// a margin so that rounding errors can only add points
+fn contains_periodic(lo: f64, hi: f64, offset: f64, period: f64) -> bool {
+    let margin = 4. * f64::EPSILON * (lo.abs().max(hi.abs()) + period);
+    let k = ((lo - margin - offset) / period).ceil();
+    offset + k * period <= hi + margin
+}
+
+// f with maxima at max_offset and minima at max_offset + pi, modulo 2 pi
+fn periodic_unit(interval: &Interval, f: fn(f64) -> f64, max_offset: f64) -> Interval {
+    let (lo, hi) = (interval.lo, interval.hi);
+    let width = hi - lo;
+    if width.is_nan() || width >= 2. * PI {
+        return Interval::new(-1., 1.);
+    }
+
+    let (at_lo, at_hi) = (f(lo), f(hi));
+    let mut result = Interval::widened(at_lo.min(at_hi), at_lo.max(at_hi));
+    if contains_periodic(lo, hi, max_offset, 2. * PI) {
+        result.hi = 1.;
+    }
+    if contains_periodic(lo, hi, max_offset + PI, 2. * PI) {
+        result.lo = -1.;
+    }
+    Interval {
+        lo: result.lo.max(-1.),
+        hi: result.hi.min(1.),
+    }
+}
+
+impl ElementaryOps for Interval {
+    fn exp_trait(&self) -> Self {
+        let result = Interval::widened(self.lo.exp(), self.hi.exp());
+        Interval {
+            lo: result.lo.max(0.),
+            ..result
+        }
+    }
+
+    // negative parts are cut off, an entirely negative interval gives NaN
+    fn ln_trait(&self) -> Self {
+        if self.hi < 0. {
+            return Interval::NAN;
+        }
+        Interval::widened(self.lo.max(0.).ln(), self.hi.ln())
+    }
+
+    fn sin_trait(&self) -> Self {
+        periodic_unit(self, f64::sin, FRAC_PI_2)
+    }
+
+    fn cos_trait(&self) -> Self {
+        periodic_unit(self, f64::cos, 0.)
+    }
+
+    // an interval reaching a pole gives the entire line
+    fn tan_trait(&self) -> Self {
+        let (lo, hi) = (self.lo, self.hi);
+        let width = hi - lo;
+        if width.is_nan() || width >= PI || contains_periodic(lo, hi, FRAC_PI_2, PI) {
+            return Interval::ENTIRE;
+        }
+        Interval::widened(lo.tan(), hi.tan())
+    }
+
+    fn atan_trait(&self) -> Self {
+        let result = Interval::widened(self.lo.atan(), self.hi.atan());
+        Interval {
+            lo: result.lo.max(-FRAC_PI_2.next_up()),
+            hi: result.hi.min(FRAC_PI_2.next_up()),
+        }
+    }
+}
@@ -1,6 +1,15 @@
 pub mod basic;
+pub mod double_double;
+pub mod dual;
+pub mod eigen;
+pub mod interval;
 pub mod iterative_methods;
 pub mod lu_decomposition;
 pub mod matrix;
+pub mod parallel;
+pub mod persistence;
 pub mod qr_decomposition;
+pub mod random;
 pub mod representation;
+pub mod summation;
+pub mod test;
@@ -2,13 +2,22 @@ use crate::{
     basic::{Index, Numerical, OtherNumericalOps, INDEX_NOT_FOUND},
     matrix::{
         column::{ColumnFuncInitializer, ColumnMut},
+        dense::DenseRowMatrix,
         row_permuted::RowPermutedMatrix,
         solve_upper::solve_upper,
-        traits::MatrixMutRef,
+        traits::{MatrixFuncInitializer, MatrixMutRef, MatrixRef},
+    },
+    parallel::Parallelism,
+    persistence::{
+        read_header, read_permutation, read_scalars, write_header, write_permutation,
+        write_scalars, FactorisationKind, PersistenceError, PersistentScalar,
     },
     representation::repr_ref,
 };
-use std::mem::swap;
+use std::{
+    io::{Read, Write},
+    mem::swap,
+};
 
 #[derive(Debug, Clone)]
 pub struct LUDecomposition<Matrix>
@@ -25,6 +34,78 @@ where
     eta_data: Vec<Matrix::Scalar>,
 }
 
+// largest by absolute value element of the column among rows without a pivot
+fn select_pivot<Matrix>(matrix: &Matrix, permutation: &[Index], column: Index) -> Index
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+{
+    let (_, pivot_row) = (0..matrix.dimension())
+        .filter(|row| permutation[*row] == INDEX_NOT_FOUND)
+        .fold(
+            (Matrix::Scalar::zero(), INDEX_NOT_FOUND),
+            |(max_value, max_index), row| {
+                let row_value = matrix.at(row, column).abs_trait();
+                if row_value >= max_value {
+                    (row_value, row)
+                } else {
+                    (max_value, max_index)
+                }
+            },
+        );
+    pivot_row
+}
+
+// the only row left without a pivot gets the last one
+fn complete_permutation(permutation: &mut [Index], inverse_permutation: &mut [Index]) {
+    let dimension = permutation.len();
+    for (row, position) in permutation.iter_mut().enumerate() {
+        if *position == INDEX_NOT_FOUND {
+            *position = dimension - 1;
+            inverse_permutation[dimension - 1] = row;
+        }
+    }
+}
+
+// adds the pivot row multiplied by factors to the rows without a pivot
+fn eliminate<Matrix>(
+    matrix: &mut Matrix,
+    permutation: &[Index],
+    factors: &[Matrix::Scalar],
+    pivot_row: Index,
+    column: Index,
+) where
+    Matrix: MatrixMutRef,
+    Matrix::Scalar: Numerical,
+{
+    let dimension = matrix.dimension();
+    let pivot_tail = matrix
+        .row_slice(pivot_row)
+        .map(|pivot_data| pivot_data[column + 1..].to_vec());
+
+    for row in 0..dimension {
+        let factor = factors[row];
+        if permutation[row] != INDEX_NOT_FOUND || factor == Matrix::Scalar::zero() {
+            continue;
+        }
+
+        match (&pivot_tail, matrix.row_slice_mut(row)) {
+            (Some(pivot_tail), Some(row_data)) => {
+                for (value, pivot) in row_data[column + 1..].iter_mut().zip(pivot_tail) {
+                    *value = *value + factor * *pivot;
+                }
+            }
+            _ => {
+                for affected_column in column + 1..dimension {
+                    let fill_in = matrix.at(row, affected_column)
+                        + factor * matrix.at(pivot_row, affected_column);
+                    *matrix.at_mut(row, affected_column) = fill_in;
+                }
+            }
+        }
+    }
+}
+
 impl<Matrix> LUDecomposition<Matrix>
 where
     Matrix: MatrixMutRef,
@@ -37,48 +118,30 @@ where
         let mut eta_data = vec![Matrix::Scalar::zero(); dimension * (dimension - 1)];
 
         for column in 0..dimension - 1 {
-            let (_, pivot_row) = (0..dimension)
-                .filter(|row| permutation[*row] == INDEX_NOT_FOUND)
-                .fold(
-                    (Matrix::Scalar::zero(), INDEX_NOT_FOUND),
-                    |(max_value, max_index), row| {
-                        let row_value = matrix.at(row, column).abs_trait();
-                        if row_value >= max_value {
-                            (row_value, row)
-                        } else {
-                            (max_value, max_index)
-                        }
-                    },
-                );
+            let pivot_row = select_pivot(&matrix, &permutation, column);
 
             permutation[pivot_row] = column;
             inverse_permutation[column] = pivot_row;
 
+            let pivot_value = matrix.at(pivot_row, column);
             for row in 0..dimension {
                 if permutation[row] == INDEX_NOT_FOUND {
-                    let factor = -matrix.at(row, column) / matrix.at(pivot_row, column);
-                    eta_data[column * dimension + row] = factor.clone();
-
+                    eta_data[column * dimension + row] = -matrix.at(row, column) / pivot_value;
                     *matrix.at_mut(row, column) = Matrix::Scalar::zero();
-
-                    if factor != Matrix::Scalar::zero() {
-                        for affected_column in column + 1..dimension {
-                            let fill_in = matrix.at(row, affected_column)
-                                + factor.clone() * matrix.at(pivot_row, affected_column);
-                            *matrix.at_mut(row, affected_column) = fill_in;
-                        }
-                    }
                 }
             }
-        }
 
-        for row in 0..dimension {
-            if permutation[row] == INDEX_NOT_FOUND {
-                permutation[row] = dimension - 1;
-                inverse_permutation[dimension - 1] = row;
-            }
+            eliminate(
+                &mut matrix,
+                &permutation,
+                &eta_data[column * dimension..(column + 1) * dimension],
+                pivot_row,
+                column,
+            );
         }
 
+        complete_permutation(&mut permutation, &mut inverse_permutation);
+
         LUDecomposition {
             // permutation,
             inverse_permutation,
@@ -87,18 +150,43 @@ where
         }
     }
 
+    // smallest absolute value of the pivots, zero for singular matricies
+    pub fn min_pivot(&self) -> Matrix::Scalar {
+        (0..self.upper_permuted.dimension())
+            .map(|k| {
+                self.upper_permuted
+                    .at(self.inverse_permutation[k], k)
+                    .abs_trait()
+            })
+            .fold(None, |min, pivot| match min {
+                Some(min) if min <= pivot => Some(min),
+                _ => Some(pivot),
+            })
+            .unwrap_or(Matrix::Scalar::zero())
+    }
+
     pub fn solve<Column>(&self, vector: &mut Column)
     where
         Column: ColumnMut<Scalar = Matrix::Scalar> + ColumnFuncInitializer<Scalar = Matrix::Scalar>,
     {
         let dimension = self.upper_permuted.dimension();
         // applying lower triangular matricies
-        for eta_index in 0..dimension - 1 {
-            let eta_column = self.inverse_permutation[eta_index];
-            let vector_value = vector.at(eta_column);
-            for index in 0..dimension {
-                *vector.at_mut(index) =
-                    vector.at(index) + self.eta_data[eta_index * dimension + index] * vector_value;
+        if let Some(values) = vector.as_mut_slice() {
+            for eta_index in 0..dimension - 1 {
+                let vector_value = values[self.inverse_permutation[eta_index]];
+                let eta_vector = &self.eta_data[eta_index * dimension..(eta_index + 1) * dimension];
+                for (value, eta) in values.iter_mut().zip(eta_vector) {
+                    *value = *value + *eta * vector_value;
+                }
+            }
+        } else {
+            for eta_index in 0..dimension - 1 {
+                let eta_column = self.inverse_permutation[eta_index];
+                let vector_value = vector.at(eta_column);
+                for index in 0..dimension {
+                    *vector.at_mut(index) = vector.at(index)
+                        + self.eta_data[eta_index * dimension + index] * vector_value;
+                }
             }
         }
 
@@ -121,3 +209,97 @@ where
         );
     }
 }
+
+impl<Matrix> LUDecomposition<Matrix>
+where
+    Matrix: MatrixMutRef + MatrixFuncInitializer,
+    Matrix::Scalar: Numerical + PersistentScalar,
+{
+    // Stores the factorisation in the binary format of persistence
+    pub fn write(&self, mut writer: impl Write) -> Result<(), PersistenceError> {
+        let dimension = self.upper_permuted.dimension();
+        let upper: Vec<Matrix::Scalar> = (0..dimension * dimension)
+            .map(|i| self.upper_permuted.at(i / dimension, i % dimension))
+            .collect();
+
+        write_header::<Matrix::Scalar>(&mut writer, FactorisationKind::LU, dimension)?;
+        write_scalars(&mut writer, &upper)?;
+        write_permutation(&mut writer, &self.inverse_permutation)?;
+        write_scalars(&mut writer, &self.eta_data)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read(mut reader: impl Read) -> Result<LUDecomposition<Matrix>, PersistenceError> {
+        let dimension = read_header::<Matrix::Scalar>(&mut reader, FactorisationKind::LU)?;
+        let upper: Vec<Matrix::Scalar> = read_scalars(&mut reader, dimension * dimension)?;
+        let inverse_permutation = read_permutation(&mut reader, dimension)?;
+        let eta_data = read_scalars(&mut reader, dimension * (dimension - 1))?;
+
+        Ok(LUDecomposition {
+            upper_permuted: Matrix::new_func(dimension, |row, column| {
+                upper[row * dimension + column]
+            }),
+            inverse_permutation,
+            eta_data,
+        })
+    }
+}
+
+impl<Scalar> LUDecomposition<DenseRowMatrix<Scalar>>
+where
+    Scalar: Numerical + Send + Sync,
+{
+    // Same elimination as calculate, the rows affected by a pivot are
+    // partitioned between threads
+    pub fn calculate_parallel(
+        mut matrix: DenseRowMatrix<Scalar>,
+        parallelism: Parallelism,
+    ) -> LUDecomposition<DenseRowMatrix<Scalar>> {
+        let dimension = matrix.dimension();
+        let mut permutation = vec![INDEX_NOT_FOUND; dimension];
+        let mut inverse_permutation = vec![INDEX_NOT_FOUND; dimension];
+        let mut eta_data = vec![Scalar::zero(); dimension * (dimension - 1)];
+
+        for column in 0..dimension - 1 {
+            let pivot_row = select_pivot(&matrix, &permutation, column);
+
+            permutation[pivot_row] = column;
+            inverse_permutation[column] = pivot_row;
+
+            let pivot_value = matrix.at(pivot_row, column);
+            let pivot_tail = matrix.data()
+                [pivot_row * dimension + column + 1..(pivot_row + 1) * dimension]
+                .to_vec();
+
+            let mut rows: Vec<(Scalar, &mut [Scalar])> = matrix
+                .data_mut()
+                .chunks_mut(dimension)
+                .enumerate()
+                .filter(|(row, _)| permutation[*row] == INDEX_NOT_FOUND)
+                .map(|(row, row_data)| {
+                    let factor = -row_data[column] / pivot_value;
+                    eta_data[column * dimension + row] = factor;
+                    row_data[column] = Scalar::zero();
+                    (factor, &mut row_data[column + 1..])
+                })
+                .collect();
+
+            parallelism.for_each_mut(&mut rows, |_, (factor, row_tail)| {
+                if *factor != Scalar::zero() {
+                    for (value, pivot) in row_tail.iter_mut().zip(pivot_tail.iter()) {
+                        *value = *value + *factor * *pivot;
+                    }
+                }
+            });
+        }
+
+        complete_permutation(&mut permutation, &mut inverse_permutation);
+
+        LUDecomposition {
+            inverse_permutation,
+            eta_data,
+            upper_permuted: matrix,
+        }
+    }
+}
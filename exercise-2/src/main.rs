@@ -1,38 +1,37 @@
-use std::{env::args, process::exit};
+use std::{env::args, iter::once, process::exit, rc::Rc};
 
-use iterative_methods::simple_iterative_solve;
-use matrix::dense::DenseRowMatrix;
-use qr_decomposition::QRDecomposition;
-use test::Scalar;
-
-use crate::{
+use bench_command::{bench_command, BENCH_USAGE};
+use exercise_2::{
     basic::Index,
-    iterative_methods::zeidel_iterative_solve,
+    iterative_methods::{simple_iterative_solve, zeidel_iterative_solve},
     lu_decomposition::LUDecomposition,
     matrix::{
         column::{ColumnFunc, ColumnRef},
+        dense::DenseRowMatrix,
         norms::NormedColumn,
+        traits::MatrixRef,
     },
-    test::{create_fifth_cases, create_static_test_cases, on_case, TestResult},
+    qr_decomposition::QRDecomposition,
+    representation::repr_cow,
+    summation::{Kahan, Naive, Neumaier, Pairwise, Summation},
+    test::{create_fifth_cases, create_static_test_cases, on_case, Scalar, TestResult},
 };
+use solve_command::{solve_command, SOLVE_USAGE};
 
-mod basic;
-mod iterative_methods;
-mod lu_decomposition;
-mod matrix;
-mod qr_decomposition;
-mod representation;
-mod test;
+mod bench_command;
+mod solve_command;
 
-fn solve_lu(matrix: &DenseRowMatrix<Scalar>, vector: &Vec<Scalar>) -> Vec<Scalar> {
-    let decomposition = LUDecomposition::calculate(matrix.clone());
+// the cases keep their matrices, the decompositions copy them on the first
+// write
+fn solve_lu(matrix: &Rc<DenseRowMatrix<Scalar>>, vector: &Vec<Scalar>) -> Vec<Scalar> {
+    let decomposition = LUDecomposition::calculate(repr_cow(matrix));
     let mut vector_mut = vector.clone();
     decomposition.solve(&mut vector_mut);
     vector_mut
 }
 
-fn solve_qr(matrix: &DenseRowMatrix<Scalar>, vector: &Vec<Scalar>) -> Vec<Scalar> {
-    let decomposition = QRDecomposition::calculate(matrix.clone());
+fn solve_qr(matrix: &Rc<DenseRowMatrix<Scalar>>, vector: &Vec<Scalar>) -> Vec<Scalar> {
+    let decomposition = QRDecomposition::calculate(repr_cow(matrix));
     let mut vector_mut = vector.clone();
     decomposition.solve(&mut vector_mut);
     vector_mut
@@ -78,6 +77,61 @@ fn dynamic_test_direct_methods() {
     }
 }
 
+// b - Ax with b as one more term, so that a compensated sum keeps the
+// residual accurate however much the products cancel
+fn residual_with<Method: Summation>(
+    matrix: &DenseRowMatrix<Scalar>,
+    vector: &[Scalar],
+    x: &[Scalar],
+) -> Vec<Scalar> {
+    (0..matrix.dimension())
+        .map(|i| {
+            let products = x.iter().enumerate().map(|(j, x)| -(matrix.at(i, j) * x));
+            Method::sum(once(vector[i]).chain(products))
+        })
+        .collect()
+}
+
+// LU followed by iterative refinement with the residuals summed by Method
+fn solve_lu_refined<Method: Summation>(
+    matrix: &Rc<DenseRowMatrix<Scalar>>,
+    vector: &Vec<Scalar>,
+) -> Vec<Scalar> {
+    let decomposition = LUDecomposition::calculate(repr_cow(matrix));
+    let mut x = vector.clone();
+    decomposition.solve(&mut x);
+
+    for _ in 0..REFINEMENT_STEPS {
+        let mut correction = residual_with::<Method>(matrix, vector, &x);
+        decomposition.solve(&mut correction);
+        for (x, correction) in x.iter_mut().zip(correction) {
+            *x += correction;
+        }
+    }
+
+    x
+}
+
+const REFINEMENT_STEPS: Index = 3;
+
+fn dynamic_test_summation() {
+    println!("Тест;n;epsilon;LU;Naive;Kahan;Neumaier;Pairwise");
+    println!(";;;d;d;d;d;d");
+
+    for case in create_fifth_cases(vec![1e-3, 1e-6, 1e-9, 1e-12], vec![4, 5, 8, 16, 32]) {
+        let errors = [
+            on_case(&case, solve_lu).norm_one,
+            on_case(&case, solve_lu_refined::<Naive>).norm_one,
+            on_case(&case, solve_lu_refined::<Kahan>).norm_one,
+            on_case(&case, solve_lu_refined::<Neumaier>).norm_one,
+            on_case(&case, solve_lu_refined::<Pairwise>).norm_one,
+        ];
+        let errors: Vec<String> = errors.iter().map(|d| format!("{d:.e}")).collect();
+
+        println!("{};{}", case.name, errors.join(";"));
+    }
+}
+
 fn static_test_iterative_methods() {
     println!("Тест;bar x;e;МПИ;;;Метод Зейделя;;");
     println!(";;;x;d;k;x;d;k");
@@ -88,14 +142,14 @@ fn static_test_iterative_methods() {
         println!("{test_n};{precise_answer:?};;;;;;;");
         for e in vec![1e-2, 1e-4, 1e-6, 1e-10] {
             let (it_answer, it_steps): (Vec<Scalar>, Index) =
-                simple_iterative_solve(&case.matrix, &case.vector, e);
+                simple_iterative_solve(case.matrix.as_ref(), &case.vector, e);
             let it_norm = ColumnFunc::new(precise_answer.dimension(), |i| {
                 it_answer[i] - precise_answer[i]
             })
             .norm_one();
 
             let (zei_answer, zei_steps): (Vec<Scalar>, Index) =
-                zeidel_iterative_solve(&case.matrix, &case.vector, e);
+                zeidel_iterative_solve(case.matrix.as_ref(), &case.vector, e);
             let zei_norm = ColumnFunc::new(precise_answer.dimension(), |i| {
                 zei_answer[i] - precise_answer[i]
             })
@@ -116,14 +170,14 @@ fn dynamic_test_iterative_methods() {
         println!("{test_n};{precise_answer:?};;;;;;;");
         for e in vec![1e-2, 1e-4, 1e-6, 1e-10] {
             let (it_answer, it_steps): (Vec<Scalar>, Index) =
-                simple_iterative_solve(&case.matrix, &case.vector, e);
+                simple_iterative_solve(case.matrix.as_ref(), &case.vector, e);
             let it_norm = ColumnFunc::new(precise_answer.dimension(), |i| {
                 it_answer[i] - precise_answer[i]
             })
             .norm_one();
 
             let (zei_answer, zei_steps): (Vec<Scalar>, Index) =
-                zeidel_iterative_solve(&case.matrix, &case.vector, e);
+                zeidel_iterative_solve(case.matrix.as_ref(), &case.vector, e);
             let zei_norm = ColumnFunc::new(precise_answer.dimension(), |i| {
                 zei_answer[i] - precise_answer[i]
             })
@@ -137,10 +191,30 @@ fn dynamic_test_iterative_methods() {
 fn main() {
     let cli_args: Vec<String> = args().collect();
 
+    if cli_args.len() > 1 && cli_args[1] == "solve" {
+        if let Err(error) = solve_command(&cli_args[2..]) {
+            println!("{error}");
+            println!("usage: [command] {SOLVE_USAGE}");
+            exit(1);
+        }
+        return;
+    }
+
+    if cli_args.len() > 1 && cli_args[1] == "bench" {
+        if let Err(error) = bench_command(&cli_args[2..]) {
+            println!("{error}");
+            println!("usage: [command] {BENCH_USAGE}");
+            exit(1);
+        }
+        return;
+    }
+
     if cli_args.len() != 2 {
         println!(
-            "usage: [command] [static-direct|dynamic-direct|static-iterative|dynamic-iterative]"
+            "usage: [command] [static-direct|dynamic-direct|static-iterative|dynamic-iterative|dynamic-summation]"
         );
+        println!("       [command] {SOLVE_USAGE}");
+        println!("       [command] {BENCH_USAGE}");
         exit(1);
     }
 
@@ -151,6 +225,7 @@ fn main() {
         "dynamic-direct" => dynamic_test_direct_methods(),
         "static-iterative" => static_test_iterative_methods(),
         "dynamic-iterative" => dynamic_test_iterative_methods(),
+        "dynamic-summation" => dynamic_test_summation(),
         _ => {
             println!("Invalid test case");
             exit(1);
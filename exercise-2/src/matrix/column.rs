@@ -3,6 +3,7 @@ use std::borrow::{Borrow, BorrowMut};
 use crate::{
     basic::{Index, Numerical},
     representation::{repr_ref, Representation},
+    summation::{Naive, Summation},
 };
 
 use super::{
@@ -14,10 +15,19 @@ pub trait ColumnRef {
     type Scalar;
     fn dimension(&self) -> Index;
     fn at(&self, index: Index) -> Self::Scalar;
+
+    // contiguous storage of the column, if it has one
+    fn as_slice(&self) -> Option<&[Self::Scalar]> {
+        None
+    }
 }
 
 pub trait ColumnMut: ColumnRef {
     fn at_mut(&mut self, index: Index) -> &mut Self::Scalar;
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Self::Scalar]> {
+        None
+    }
 }
 
 pub trait ColumnFuncInitializer: Sized + ColumnRef {
@@ -51,6 +61,10 @@ where
     fn at(&self, index: Index) -> Scalar {
         self[index].clone()
     }
+
+    fn as_slice(&self) -> Option<&[Scalar]> {
+        Some(self)
+    }
 }
 
 impl<Scalar> ColumnMut for Vec<Scalar>
@@ -60,6 +74,10 @@ where
     fn at_mut(&mut self, index: Index) -> &mut Scalar {
         &mut self[index]
     }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Scalar]> {
+        Some(self)
+    }
 }
 
 impl<Scalar> ColumnFuncInitializer for Vec<Scalar>
@@ -81,7 +99,35 @@ where
     Lhs: ColumnRef<Scalar = Rhs::Scalar>,
     Rhs::Scalar: Numerical,
 {
-    (0..rhs.dimension()).map(|i| rhs.at(i) * lhs.at(i)).sum()
+    dot_with::<Naive, _, _>(rhs, lhs)
+}
+
+pub fn dot_with<Method, Rhs, Lhs>(rhs: &Rhs, lhs: &Lhs) -> Rhs::Scalar
+where
+    Method: Summation,
+    Rhs: ColumnRef,
+    Lhs: ColumnRef<Scalar = Rhs::Scalar>,
+    Rhs::Scalar: Numerical,
+{
+    match (rhs.as_slice(), lhs.as_slice()) {
+        (Some(rhs), Some(lhs)) => dot_slices_with::<Method, _>(rhs, lhs),
+        _ => Method::sum((0..rhs.dimension()).map(|i| rhs.at(i) * lhs.at(i))),
+    }
+}
+
+pub fn dot_slices<Scalar>(rhs: &[Scalar], lhs: &[Scalar]) -> Scalar
+where
+    Scalar: Numerical,
+{
+    dot_slices_with::<Naive, _>(rhs, lhs)
+}
+
+pub fn dot_slices_with<Method, Scalar>(rhs: &[Scalar], lhs: &[Scalar]) -> Scalar
+where
+    Method: Summation,
+    Scalar: Numerical,
+{
+    Method::sum_products(rhs, lhs)
 }
 
 pub fn apply<Matrix, ColumnIn, ColumnOut>(matrix: &Matrix, column: &ColumnIn) -> ColumnOut
@@ -91,7 +137,23 @@ where
     ColumnIn: ColumnRef<Scalar = Matrix::Scalar>,
     ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
 {
-    ColumnOut::new_func(matrix.dimension(), |i| apply_at(matrix, column, i))
+    apply_with::<Naive, _, _, _>(matrix, column)
+}
+
+pub fn apply_with<Method, Matrix, ColumnIn, ColumnOut>(
+    matrix: &Matrix,
+    column: &ColumnIn,
+) -> ColumnOut
+where
+    Method: Summation,
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    ColumnIn: ColumnRef<Scalar = Matrix::Scalar>,
+    ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
+{
+    ColumnOut::new_func(matrix.dimension(), |i| {
+        apply_at_with::<Method, _, _>(matrix, column, i)
+    })
 }
 
 pub fn apply_at<Matrix, ColumnIn>(matrix: &Matrix, column: &ColumnIn, i: Index) -> Matrix::Scalar
@@ -99,11 +161,28 @@ where
     Matrix: MatrixRef,
     Matrix::Scalar: Numerical,
     ColumnIn: ColumnRef<Scalar = Matrix::Scalar>,
+{
+    apply_at_with::<Naive, _, _>(matrix, column, i)
+}
+
+pub fn apply_at_with<Method, Matrix, ColumnIn>(
+    matrix: &Matrix,
+    column: &ColumnIn,
+    i: Index,
+) -> Matrix::Scalar
+where
+    Method: Summation,
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    ColumnIn: ColumnRef<Scalar = Matrix::Scalar>,
 {
     // (0..column.dimension())
     //     .map(|j| matrix.at(i, j) * column.at(j))
     //     .sum()
-    dot(
+    if let (Some(row), Some(column)) = (matrix.row_slice(i), column.as_slice()) {
+        return dot_slices_with::<Method, _>(row, column);
+    }
+    dot_with::<Method, _, _>(
         &ColumnOf::new(MatrixTranspose::from(repr_ref::<Matrix>(matrix)), i),
         column,
     )
@@ -174,6 +253,10 @@ where
     fn at(&self, index: Index) -> Matrix::Scalar {
         self.matrix.at(index, self.column)
     }
+
+    fn as_slice(&self) -> Option<&[Matrix::Scalar]> {
+        self.matrix.column_slice(self.column)
+    }
 }
 
 impl<Matrix> ColumnMut for ColumnOf<Matrix>
@@ -183,6 +266,10 @@ where
     fn at_mut(&mut self, index: Index) -> &mut Matrix::Scalar {
         self.matrix.at_mut(index, self.column)
     }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Matrix::Scalar]> {
+        self.matrix.column_slice_mut(self.column)
+    }
 }
 
 impl<Stored, Impl> ColumnRef for Representation<Stored, Impl>
@@ -199,6 +286,10 @@ where
     fn at(&self, index: Index) -> Self::Scalar {
         self.represent().at(index)
     }
+
+    fn as_slice(&self) -> Option<&[Self::Scalar]> {
+        self.represent().as_slice()
+    }
 }
 
 impl<Stored, Impl> ColumnMut for Representation<Stored, Impl>
@@ -209,4 +300,8 @@ where
     fn at_mut(&mut self, index: Index) -> &mut Self::Scalar {
         self.represent_mut().at_mut(index)
     }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Self::Scalar]> {
+        self.represent_mut().as_mut_slice()
+    }
 }
@@ -0,0 +1,67 @@
+use crate::basic::Index;
+
+use super::{
+    permutation::Permutation,
+    traits::{MatrixMutRef, MatrixRef},
+};
+
+// Represents a lazy column permuted accessor for M P
+#[derive(Clone, Debug)]
+pub struct ColumnPermutedMatrix<Matrix, Perm> {
+    matrix: Matrix,
+    column_permutation: Perm,
+}
+
+impl<Matrix, Perm> ColumnPermutedMatrix<Matrix, Perm> {
+    pub fn new(matrix: Matrix, column_permutation: Perm) -> ColumnPermutedMatrix<Matrix, Perm> {
+        ColumnPermutedMatrix {
+            matrix,
+            column_permutation,
+        }
+    }
+
+    pub fn inner(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Matrix {
+        &mut self.matrix
+    }
+}
+
+impl<Matrix, Perm> MatrixRef for ColumnPermutedMatrix<Matrix, Perm>
+where
+    Matrix: MatrixRef,
+    Perm: Permutation,
+{
+    type Scalar = Matrix::Scalar;
+
+    fn dimension(&self) -> Index {
+        self.matrix.dimension()
+    }
+
+    fn at(&self, row: Index, column: Index) -> Self::Scalar {
+        self.matrix.at(row, self.column_permutation.permute(column))
+    }
+
+    fn column_slice(&self, column: Index) -> Option<&[Self::Scalar]> {
+        self.matrix
+            .column_slice(self.column_permutation.permute(column))
+    }
+}
+
+impl<Matrix, Perm> MatrixMutRef for ColumnPermutedMatrix<Matrix, Perm>
+where
+    Matrix: MatrixMutRef,
+    Perm: Permutation,
+{
+    fn at_mut(&mut self, row: Index, column: Index) -> &mut Self::Scalar {
+        self.matrix
+            .at_mut(row, self.column_permutation.permute(column))
+    }
+
+    fn column_slice_mut(&mut self, column: Index) -> Option<&mut [Self::Scalar]> {
+        self.matrix
+            .column_slice_mut(self.column_permutation.permute(column))
+    }
+}
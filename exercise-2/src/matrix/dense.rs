@@ -17,6 +17,15 @@ impl<Scalar> DenseColMatrix<Scalar> {
     pub fn new(dimension: Index, data: Vec<Scalar>) -> Self {
         Self { dimension, data }
     }
+
+    // column major storage
+    pub fn data(&self) -> &[Scalar] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [Scalar] {
+        &mut self.data
+    }
 }
 
 impl<Scalar> MatrixRef for DenseColMatrix<Scalar>
@@ -32,6 +41,10 @@ where
     fn at(&self, row: Index, column: Index) -> Scalar {
         self.data[self.data_index(row, column)].clone()
     }
+
+    fn column_slice(&self, column: Index) -> Option<&[Scalar]> {
+        Some(&self.data[column * self.dimension..(column + 1) * self.dimension])
+    }
 }
 
 impl<Scalar> MatrixMutRef for DenseColMatrix<Scalar>
@@ -42,6 +55,10 @@ where
         let data_index = self.data_index(row, column);
         &mut self.data[data_index]
     }
+
+    fn column_slice_mut(&mut self, column: Index) -> Option<&mut [Scalar]> {
+        Some(&mut self.data[column * self.dimension..(column + 1) * self.dimension])
+    }
 }
 
 impl<Scalar> MatrixFuncInitializer for DenseColMatrix<Scalar>
@@ -67,4 +84,13 @@ impl<Scalar> DenseRowMatrix<Scalar> {
     pub fn new(dimension: Index, data: Vec<Scalar>) -> Self {
         Self::from(DenseColMatrix::new(dimension, data))
     }
+
+    // row major storage
+    pub fn data(&self) -> &[Scalar] {
+        self.inner().data()
+    }
+
+    pub fn data_mut(&mut self) -> &mut [Scalar] {
+        self.inner_mut().data_mut()
+    }
 }
@@ -0,0 +1,561 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use crate::basic::{Index, Numerical};
+
+use super::{
+    column::ColumnRef,
+    sparse::SparseRowMatrix,
+    traits::{MatrixFuncInitializer, MatrixRef},
+};
+
+// Reading and writing of the Matrix Market exchange format
+// https://math.nist.gov/MatrixMarket/formats.html
+// Only real matrices are supported, matrices have to be square since all
+// matrices here are square, vectors are n x 1 matrices
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixMarketFormat {
+    Coordinate,
+    Array,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixMarketField {
+    Real,
+    Integer,
+    Pattern,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixMarketSymmetry {
+    General,
+    Symmetric,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatrixMarketHeader {
+    pub format: MatrixMarketFormat,
+    pub field: MatrixMarketField,
+    pub symmetry: MatrixMarketSymmetry,
+}
+
+#[derive(Debug)]
+pub enum MatrixMarketError {
+    Io(io::Error),
+    MissingHeader,
+    InvalidHeader {
+        line: String,
+    },
+    Unsupported {
+        what: String,
+    },
+    MissingSize,
+    InvalidSize {
+        line: usize,
+    },
+    NotSquare {
+        rows: Index,
+        columns: Index,
+    },
+    InvalidEntry {
+        line: usize,
+    },
+    IndexOutOfRange {
+        line: usize,
+        row: Index,
+        column: Index,
+    },
+    UpperTriangleEntry {
+        line: usize,
+        row: Index,
+        column: Index,
+    },
+    EntryCount {
+        expected: Index,
+        found: Index,
+    },
+    NotSymmetric {
+        row: Index,
+        column: Index,
+    },
+    NotVector {
+        rows: Index,
+        columns: Index,
+    },
+}
+
+impl Display for MatrixMarketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixMarketError::Io(error) => write!(f, "io error: {error}"),
+            MatrixMarketError::MissingHeader => write!(f, "missing %%MatrixMarket header"),
+            MatrixMarketError::InvalidHeader { line } => write!(f, "invalid header \"{line}\""),
+            MatrixMarketError::Unsupported { what } => write!(f, "unsupported {what}"),
+            MatrixMarketError::MissingSize => write!(f, "missing size line"),
+            MatrixMarketError::InvalidSize { line } => write!(f, "line {line}: invalid size line"),
+            MatrixMarketError::NotSquare { rows, columns } => {
+                write!(f, "matrix is not square ({rows}x{columns})")
+            }
+            MatrixMarketError::InvalidEntry { line } => write!(f, "line {line}: invalid entry"),
+            MatrixMarketError::IndexOutOfRange { line, row, column } => {
+                write!(f, "line {line}: index ({row}, {column}) is out of range")
+            }
+            MatrixMarketError::UpperTriangleEntry { line, row, column } => write!(
+                f,
+                "line {line}: entry ({row}, {column}) is above the diagonal of a symmetric matrix"
+            ),
+            MatrixMarketError::EntryCount { expected, found } => {
+                write!(f, "expected {expected} entries, found {found}")
+            }
+            MatrixMarketError::NotSymmetric { row, column } => {
+                write!(f, "matrix is not symmetric at ({row}, {column})")
+            }
+            MatrixMarketError::NotVector { rows, columns } => {
+                write!(f, "matrix is not a vector ({rows}x{columns})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatrixMarketError {}
+
+impl From<io::Error> for MatrixMarketError {
+    fn from(error: io::Error) -> Self {
+        MatrixMarketError::Io(error)
+    }
+}
+
+impl Display for MatrixMarketHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match self.format {
+            MatrixMarketFormat::Coordinate => "coordinate",
+            MatrixMarketFormat::Array => "array",
+        };
+        let field = match self.field {
+            MatrixMarketField::Real => "real",
+            MatrixMarketField::Integer => "integer",
+            MatrixMarketField::Pattern => "pattern",
+        };
+        let symmetry = match self.symmetry {
+            MatrixMarketSymmetry::General => "general",
+            MatrixMarketSymmetry::Symmetric => "symmetric",
+        };
+        write!(f, "%%MatrixMarket matrix {format} {field} {symmetry}")
+    }
+}
+
+impl FromStr for MatrixMarketHeader {
+    type Err = MatrixMarketError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let invalid = || MatrixMarketError::InvalidHeader {
+            line: String::from(line),
+        };
+        let unsupported = |what: &str| MatrixMarketError::Unsupported {
+            what: String::from(what),
+        };
+
+        let words: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+        if words.len() != 5 || words[0] != "%%matrixmarket" {
+            return Err(invalid());
+        }
+        if words[1] != "matrix" {
+            return Err(unsupported(&format!("object \"{}\"", words[1])));
+        }
+
+        let format = match words[2].as_str() {
+            "coordinate" => MatrixMarketFormat::Coordinate,
+            "array" => MatrixMarketFormat::Array,
+            _ => return Err(invalid()),
+        };
+        let field = match words[3].as_str() {
+            "real" | "double" => MatrixMarketField::Real,
+            "integer" => MatrixMarketField::Integer,
+            "pattern" => MatrixMarketField::Pattern,
+            "complex" => return Err(unsupported("field \"complex\"")),
+            _ => return Err(invalid()),
+        };
+        let symmetry = match words[4].as_str() {
+            "general" => MatrixMarketSymmetry::General,
+            "symmetric" => MatrixMarketSymmetry::Symmetric,
+            "skew-symmetric" | "hermitian" => {
+                return Err(unsupported(&format!("symmetry \"{}\"", words[4])))
+            }
+            _ => return Err(invalid()),
+        };
+
+        if format == MatrixMarketFormat::Array && field == MatrixMarketField::Pattern {
+            return Err(unsupported("pattern field in array format"));
+        }
+
+        Ok(MatrixMarketHeader {
+            format,
+            field,
+            symmetry,
+        })
+    }
+}
+
+// Contents of a Matrix Market file
+#[derive(Clone, Debug)]
+pub struct MatrixMarket<Scalar> {
+    pub header: MatrixMarketHeader,
+    pub dimension: Index,
+    // zero based (row, column, value), entries of symmetric matrices are
+    // mirrored to the upper triangle
+    pub entries: Vec<(Index, Index, Scalar)>,
+}
+
+impl<Scalar> MatrixMarket<Scalar>
+where
+    Scalar: Numerical,
+{
+    pub fn to_dense<Matrix>(&self) -> Matrix
+    where
+        Matrix: MatrixFuncInitializer<Scalar = Scalar>,
+    {
+        let mut data = vec![Scalar::zero(); self.dimension * self.dimension];
+        for (row, column, value) in &self.entries {
+            data[row * self.dimension + column] = data[row * self.dimension + column] + *value;
+        }
+        Matrix::new_func(self.dimension, |row, column| {
+            data[row * self.dimension + column]
+        })
+    }
+
+    pub fn to_sparse(&self) -> SparseRowMatrix<Scalar> {
+        SparseRowMatrix::from_entries(self.dimension, self.entries.clone())
+    }
+}
+
+// next line which is not empty or a comment
+fn next_data_line(
+    lines: &mut impl Iterator<Item = (usize, io::Result<String>)>,
+) -> Result<Option<(usize, String)>, MatrixMarketError> {
+    for (number, line) in lines {
+        let line = line?;
+        let trimmed = line.trim();
+        if !trimmed.is_empty() && !trimmed.starts_with('%') {
+            return Ok(Some((number, String::from(trimmed))));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_value<Scalar>(
+    field: MatrixMarketField,
+    token: Option<&str>,
+    line: usize,
+) -> Result<Scalar, MatrixMarketError>
+where
+    Scalar: Numerical + FromStr,
+{
+    let invalid = MatrixMarketError::InvalidEntry { line };
+    match field {
+        MatrixMarketField::Pattern => match token {
+            None => Ok(Scalar::one()),
+            Some(_) => Err(invalid),
+        },
+        MatrixMarketField::Integer => {
+            let token = token.ok_or(MatrixMarketError::InvalidEntry { line })?;
+            token.parse::<i64>().map_err(|_| invalid)?;
+            token
+                .parse()
+                .map_err(|_| MatrixMarketError::InvalidEntry { line })
+        }
+        MatrixMarketField::Real => token
+            .ok_or(MatrixMarketError::InvalidEntry { line })?
+            .parse()
+            .map_err(|_| invalid),
+    }
+}
+
+fn parse_index(token: Option<&str>, line: usize) -> Result<Index, MatrixMarketError> {
+    token
+        .and_then(|token| token.parse::<Index>().ok())
+        .ok_or(MatrixMarketError::InvalidEntry { line })
+}
+
+// header, rows, columns and zero based entries of a file of any shape
+type RawMatrixMarket<Scalar> = (
+    MatrixMarketHeader,
+    Index,
+    Index,
+    Vec<(Index, Index, Scalar)>,
+);
+
+fn read_raw<Scalar>(reader: impl BufRead) -> Result<RawMatrixMarket<Scalar>, MatrixMarketError>
+where
+    Scalar: Numerical + FromStr,
+{
+    let mut lines = reader.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    let header: MatrixMarketHeader = match lines.next() {
+        Some((_, line)) => line?.parse()?,
+        None => return Err(MatrixMarketError::MissingHeader),
+    };
+    let symmetric = header.symmetry == MatrixMarketSymmetry::Symmetric;
+
+    let (size_line, size) = next_data_line(&mut lines)?.ok_or(MatrixMarketError::MissingSize)?;
+    let size: Vec<Index> = size
+        .split_whitespace()
+        .map(|token| token.parse::<Index>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| MatrixMarketError::InvalidSize { line: size_line })?;
+    let expected_size_len = match header.format {
+        MatrixMarketFormat::Coordinate => 3,
+        MatrixMarketFormat::Array => 2,
+    };
+    if size.len() != expected_size_len {
+        return Err(MatrixMarketError::InvalidSize { line: size_line });
+    }
+    let (rows, columns) = (size[0], size[1]);
+    if symmetric && rows != columns {
+        return Err(MatrixMarketError::NotSquare { rows, columns });
+    }
+
+    let mut entries = vec![];
+    let mut push_entry = |row: Index, column: Index, value: Scalar| {
+        entries.push((row, column, value));
+        if symmetric && row != column {
+            entries.push((column, row, value));
+        }
+    };
+
+    // the size line is untrusted, counts that overflow are invalid
+    let expected = match header.format {
+        MatrixMarketFormat::Coordinate => Some(size[2]),
+        MatrixMarketFormat::Array if symmetric => rows
+            .checked_add(1)
+            .and_then(|next| rows.checked_mul(next))
+            .map(|count| count / 2),
+        MatrixMarketFormat::Array => rows.checked_mul(columns),
+    }
+    .ok_or(MatrixMarketError::InvalidSize { line: size_line })?;
+    // position of the next value of the array format in column major order
+    let (mut array_row, mut array_column) = (0, 0);
+
+    for found in 0..expected {
+        let (line, data) = match next_data_line(&mut lines)? {
+            Some(line) => line,
+            None => return Err(MatrixMarketError::EntryCount { expected, found }),
+        };
+        let mut tokens = data.split_whitespace();
+
+        match header.format {
+            MatrixMarketFormat::Coordinate => {
+                let row = parse_index(tokens.next(), line)?;
+                let column = parse_index(tokens.next(), line)?;
+                let value = parse_value(header.field, tokens.next(), line)?;
+
+                if row == 0 || column == 0 || row > rows || column > columns {
+                    return Err(MatrixMarketError::IndexOutOfRange { line, row, column });
+                }
+                if symmetric && row < column {
+                    return Err(MatrixMarketError::UpperTriangleEntry { line, row, column });
+                }
+
+                push_entry(row - 1, column - 1, value);
+            }
+            MatrixMarketFormat::Array => {
+                let value = parse_value(header.field, tokens.next(), line)?;
+                push_entry(array_row, array_column, value);
+
+                array_row += 1;
+                if array_row == rows {
+                    array_column += 1;
+                    array_row = if symmetric { array_column } else { 0 };
+                }
+            }
+        }
+
+        if tokens.next().is_some() {
+            return Err(MatrixMarketError::InvalidEntry { line });
+        }
+    }
+
+    let mut found = expected;
+    while next_data_line(&mut lines)?.is_some() {
+        found += 1;
+    }
+    if found != expected {
+        return Err(MatrixMarketError::EntryCount { expected, found });
+    }
+
+    Ok((header, rows, columns, entries))
+}
+
+pub fn read_matrix_market<Scalar>(
+    reader: impl BufRead,
+) -> Result<MatrixMarket<Scalar>, MatrixMarketError>
+where
+    Scalar: Numerical + FromStr,
+{
+    let (header, rows, columns, entries) = read_raw(reader)?;
+    if rows != columns {
+        return Err(MatrixMarketError::NotSquare { rows, columns });
+    }
+
+    Ok(MatrixMarket {
+        header,
+        dimension: rows,
+        entries,
+    })
+}
+
+// Reads a n x 1 matrix
+pub fn read_vector<Scalar>(reader: impl BufRead) -> Result<Vec<Scalar>, MatrixMarketError>
+where
+    Scalar: Numerical + FromStr,
+{
+    let (_, rows, columns, entries) = read_raw(reader)?;
+    if columns != 1 {
+        return Err(MatrixMarketError::NotVector { rows, columns });
+    }
+
+    let mut vector = vec![Scalar::zero(); rows];
+    for (row, _, value) in entries {
+        vector[row] = vector[row] + value;
+    }
+    Ok(vector)
+}
+
+pub fn read_dense<Matrix>(reader: impl BufRead) -> Result<Matrix, MatrixMarketError>
+where
+    Matrix: MatrixFuncInitializer,
+    Matrix::Scalar: Numerical + FromStr,
+{
+    Ok(read_matrix_market(reader)?.to_dense())
+}
+
+pub fn read_sparse<Scalar>(
+    reader: impl BufRead,
+) -> Result<SparseRowMatrix<Scalar>, MatrixMarketError>
+where
+    Scalar: Numerical + FromStr,
+{
+    Ok(read_matrix_market(reader)?.to_sparse())
+}
+
+fn check_symmetric<Matrix>(matrix: &Matrix) -> Result<(), MatrixMarketError>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+{
+    for column in 0..matrix.dimension() {
+        for row in column + 1..matrix.dimension() {
+            if matrix.at(row, column) != matrix.at(column, row) {
+                return Err(MatrixMarketError::NotSymmetric { row, column });
+            }
+        }
+    }
+    Ok(())
+}
+
+// Writes every element, for symmetric matrices only the lower triangle
+pub fn write_array<Matrix>(
+    writer: &mut impl Write,
+    matrix: &Matrix,
+    field: MatrixMarketField,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), MatrixMarketError>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical + Display,
+{
+    let header = MatrixMarketHeader {
+        format: MatrixMarketFormat::Array,
+        field,
+        symmetry,
+    };
+    if field == MatrixMarketField::Pattern {
+        return Err(MatrixMarketError::Unsupported {
+            what: String::from("pattern field in array format"),
+        });
+    }
+    if symmetry == MatrixMarketSymmetry::Symmetric {
+        check_symmetric(matrix)?;
+    }
+
+    let dimension = matrix.dimension();
+    writeln!(writer, "{header}")?;
+    writeln!(writer, "{dimension} {dimension}")?;
+    for column in 0..dimension {
+        let first_row = match symmetry {
+            MatrixMarketSymmetry::General => 0,
+            MatrixMarketSymmetry::Symmetric => column,
+        };
+        for row in first_row..dimension {
+            writeln!(writer, "{}", matrix.at(row, column))?;
+        }
+    }
+    Ok(())
+}
+
+// Writes a n x 1 matrix in array format
+pub fn write_vector<Column>(
+    writer: &mut impl Write,
+    vector: &Column,
+) -> Result<(), MatrixMarketError>
+where
+    Column: ColumnRef,
+    Column::Scalar: Display,
+{
+    let header = MatrixMarketHeader {
+        format: MatrixMarketFormat::Array,
+        field: MatrixMarketField::Real,
+        symmetry: MatrixMarketSymmetry::General,
+    };
+
+    writeln!(writer, "{header}")?;
+    writeln!(writer, "{} 1", vector.dimension())?;
+    for i in 0..vector.dimension() {
+        writeln!(writer, "{}", vector.at(i))?;
+    }
+    Ok(())
+}
+
+// Writes the stored elements, for symmetric matrices only the lower triangle
+pub fn write_coordinate<Scalar>(
+    writer: &mut impl Write,
+    matrix: &SparseRowMatrix<Scalar>,
+    field: MatrixMarketField,
+    symmetry: MatrixMarketSymmetry,
+) -> Result<(), MatrixMarketError>
+where
+    Scalar: Numerical + Display,
+{
+    let header = MatrixMarketHeader {
+        format: MatrixMarketFormat::Coordinate,
+        field,
+        symmetry,
+    };
+    if symmetry == MatrixMarketSymmetry::Symmetric {
+        if let Some((row, column, _)) = matrix
+            .entries()
+            .find(|(row, column, value)| matrix.at(*column, *row) != **value)
+        {
+            return Err(MatrixMarketError::NotSymmetric { row, column });
+        }
+    }
+
+    let entries: Vec<(Index, Index, &Scalar)> = matrix
+        .entries()
+        .filter(|(row, column, _)| symmetry == MatrixMarketSymmetry::General || row >= column)
+        .collect();
+
+    let dimension = matrix.dimension();
+    writeln!(writer, "{header}")?;
+    writeln!(writer, "{dimension} {dimension} {}", entries.len())?;
+    for (row, column, value) in entries {
+        match field {
+            MatrixMarketField::Pattern => writeln!(writer, "{} {}", row + 1, column + 1)?,
+            _ => writeln!(writer, "{} {} {}", row + 1, column + 1, value)?,
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,12 @@
 pub mod column;
+pub mod column_permuted;
 pub mod dense;
+pub mod market;
 pub mod norms;
 pub mod permutation;
 pub mod row_permuted;
 pub mod solve_upper;
+pub mod sparse;
 pub mod traits;
 pub mod transpose;
+pub mod triangular;
@@ -1,6 +1,7 @@
 use crate::{
     basic::{Numerical, OtherNumericalOps},
     representation::repr_ref,
+    summation::{Naive, Summation},
 };
 
 use super::{
@@ -10,7 +11,10 @@ use super::{
 };
 
 pub trait NormedColumn: ColumnRef {
-    fn norm_one(&self) -> Self::Scalar;
+    fn norm_one(&self) -> Self::Scalar {
+        self.norm_one_with::<Naive>()
+    }
+    fn norm_one_with<Method: Summation>(&self) -> Self::Scalar;
     fn norm_inf(&self) -> Self::Scalar;
 }
 
@@ -19,11 +23,8 @@ where
     T: ColumnRef,
     T::Scalar: Numerical,
 {
-    fn norm_one(&self) -> Self::Scalar
-    where
-        Self::Scalar: Numerical,
-    {
-        (0..self.dimension()).map(|i| self.at(i).abs_trait()).sum()
+    fn norm_one_with<Method: Summation>(&self) -> Self::Scalar {
+        Method::sum((0..self.dimension()).map(|i| self.at(i).abs_trait()))
     }
 
     fn norm_inf(&self) -> Self::Scalar
@@ -37,8 +38,14 @@ where
 }
 
 pub trait NormedMatrix: MatrixRef {
-    fn norm_one(&self) -> Self::Scalar;
-    fn norm_inf(&self) -> Self::Scalar;
+    fn norm_one(&self) -> Self::Scalar {
+        self.norm_one_with::<Naive>()
+    }
+    fn norm_one_with<Method: Summation>(&self) -> Self::Scalar;
+    fn norm_inf(&self) -> Self::Scalar {
+        self.norm_inf_with::<Naive>()
+    }
+    fn norm_inf_with<Method: Summation>(&self) -> Self::Scalar;
 }
 
 impl<T> NormedMatrix for T
@@ -46,13 +53,13 @@ where
     T: MatrixRef,
     T::Scalar: Numerical,
 {
-    fn norm_one(&self) -> Self::Scalar {
+    fn norm_one_with<Method: Summation>(&self) -> Self::Scalar {
         (0..self.dimension())
-            .map(|j| ColumnOf::new(repr_ref::<T>(&self), j).norm_one())
+            .map(|j| ColumnOf::new(repr_ref::<T>(self), j).norm_one_with::<Method>())
             .fold(Self::Scalar::zero(), |v, m| if v > m { v } else { m })
     }
 
-    fn norm_inf(&self) -> Self::Scalar {
-        MatrixTranspose::from(repr_ref::<T>(&self)).norm_one()
+    fn norm_inf_with<Method: Summation>(&self) -> Self::Scalar {
+        MatrixTranspose::from(repr_ref::<T>(self)).norm_one_with::<Method>()
     }
 }
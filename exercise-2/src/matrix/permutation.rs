@@ -1,7 +1,12 @@
-use std::borrow::Borrow;
+use std::{
+    borrow::Borrow,
+    fmt::{self, Display},
+};
 
 use crate::{basic::Index, representation::Representation};
 
+use super::{column::ColumnMut, traits::MatrixMutRef};
+
 pub trait Permutation {
     fn permute(&self, index: Index) -> Index;
 }
@@ -21,3 +26,173 @@ where
         self.represent().permute(index)
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PermutationError {
+    OutOfRange { index: Index, dimension: Index },
+    Repeated { index: Index },
+}
+
+impl Display for PermutationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermutationError::OutOfRange { index, dimension } => {
+                write!(f, "index {index} is out of range for dimension {dimension}")
+            }
+            PermutationError::Repeated { index } => write!(f, "index {index} is repeated"),
+        }
+    }
+}
+
+impl std::error::Error for PermutationError {}
+
+// Bijection of 0..dimension, index i is mapped to map[i]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexPermutation {
+    map: Vec<Index>,
+}
+
+impl IndexPermutation {
+    pub fn identity(dimension: Index) -> Self {
+        Self {
+            map: (0..dimension).collect(),
+        }
+    }
+
+    // swaps first and second
+    pub fn transposition(dimension: Index, first: Index, second: Index) -> Self {
+        let mut permutation = Self::identity(dimension);
+        permutation.map.swap(first, second);
+        permutation
+    }
+
+    pub fn dimension(&self) -> Index {
+        self.map.len()
+    }
+
+    pub fn as_slice(&self) -> &[Index] {
+        &self.map
+    }
+
+    pub fn into_vec(self) -> Vec<Index> {
+        self.map
+    }
+
+    // (self * other)(i) = self(other(i))
+    pub fn compose(&self, other: &IndexPermutation) -> IndexPermutation {
+        assert_eq!(self.dimension(), other.dimension());
+        Self {
+            map: other.map.iter().map(|index| self.map[*index]).collect(),
+        }
+    }
+
+    pub fn inverse(&self) -> IndexPermutation {
+        let mut map = vec![0; self.dimension()];
+        for (index, image) in self.map.iter().enumerate() {
+            map[*image] = index;
+        }
+        Self { map }
+    }
+
+    // cycles of length at least two, every cycle starts at its smallest index
+    // and continues by applying the permutation, cycles are ordered by their
+    // first index
+    pub fn cycles(&self) -> Vec<Vec<Index>> {
+        let mut visited = vec![false; self.dimension()];
+        let mut cycles = vec![];
+
+        for start in 0..self.dimension() {
+            if visited[start] || self.map[start] == start {
+                continue;
+            }
+
+            let mut cycle = vec![];
+            let mut index = start;
+            while !visited[index] {
+                visited[index] = true;
+                cycle.push(index);
+                index = self.map[index];
+            }
+            cycles.push(cycle);
+        }
+
+        cycles
+    }
+
+    // 1 for even permutations, -1 for odd
+    pub fn sign(&self) -> i32 {
+        let transpositions: Index = self.cycles().iter().map(|cycle| cycle.len() - 1).sum();
+        if transpositions.is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.sign() == 1
+    }
+
+    // column[i] becomes column[p(i)], the same order as RowPermutedMatrix
+    // gives to rows
+    pub fn permute_column<Column>(&self, column: &mut Column)
+    where
+        Column: ColumnMut,
+    {
+        assert_eq!(self.dimension(), column.dimension());
+        // a cycle is the product of swaps of its neighbouring elements
+        for cycle in self.cycles() {
+            for pair in cycle.windows(2) {
+                let value = column.at(pair[0]);
+                *column.at_mut(pair[0]) = column.at(pair[1]);
+                *column.at_mut(pair[1]) = value;
+            }
+        }
+    }
+
+    // column i of the matrix becomes column p(i), the same order as
+    // ColumnPermutedMatrix gives
+    pub fn permute_matrix_columns<Matrix>(&self, matrix: &mut Matrix)
+    where
+        Matrix: MatrixMutRef,
+    {
+        assert_eq!(self.dimension(), matrix.dimension());
+        for cycle in self.cycles() {
+            for pair in cycle.windows(2) {
+                for row in 0..matrix.dimension() {
+                    let value = matrix.at(row, pair[0]);
+                    *matrix.at_mut(row, pair[0]) = matrix.at(row, pair[1]);
+                    *matrix.at_mut(row, pair[1]) = value;
+                }
+            }
+        }
+    }
+}
+
+impl TryFrom<Vec<Index>> for IndexPermutation {
+    type Error = PermutationError;
+
+    fn try_from(map: Vec<Index>) -> Result<Self, Self::Error> {
+        let dimension = map.len();
+        let mut seen = vec![false; dimension];
+        for index in map.iter() {
+            match seen.get_mut(*index) {
+                None => {
+                    return Err(PermutationError::OutOfRange {
+                        index: *index,
+                        dimension,
+                    })
+                }
+                Some(true) => return Err(PermutationError::Repeated { index: *index }),
+                Some(seen) => *seen = true,
+            }
+        }
+        Ok(Self { map })
+    }
+}
+
+impl Permutation for IndexPermutation {
+    fn permute(&self, index: Index) -> Index {
+        self.map[index]
+    }
+}
@@ -19,6 +19,14 @@ impl<Matrix, Perm> RowPermutedMatrix<Matrix, Perm> {
             row_permutation,
         }
     }
+
+    pub fn inner(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Matrix {
+        &mut self.matrix
+    }
 }
 
 impl<Matrix, Perm> MatrixRef for RowPermutedMatrix<Matrix, Perm>
@@ -35,6 +43,10 @@ where
     fn at(&self, row: Index, column: Index) -> Self::Scalar {
         self.matrix.at(self.row_permutation.permute(row), column)
     }
+
+    fn row_slice(&self, row: Index) -> Option<&[Self::Scalar]> {
+        self.matrix.row_slice(self.row_permutation.permute(row))
+    }
 }
 
 impl<Matrix, Perm> MatrixMutRef for RowPermutedMatrix<Matrix, Perm>
@@ -46,4 +58,8 @@ where
         self.matrix
             .at_mut(self.row_permutation.permute(row), column)
     }
+
+    fn row_slice_mut(&mut self, row: Index) -> Option<&mut [Self::Scalar]> {
+        self.matrix.row_slice_mut(self.row_permutation.permute(row))
+    }
 }
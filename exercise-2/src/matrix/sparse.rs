@@ -0,0 +1,111 @@
+use crate::basic::{Index, Numerical, OtherNumericalOps};
+
+use super::traits::MatrixRef;
+
+// Sparse matrix in compressed row format
+#[derive(Clone, Debug)]
+pub struct SparseRowMatrix<Scalar> {
+    dimension: Index,
+    // row r occupies row_starts[r]..row_starts[r+1] of columns and values,
+    // columns are sorted inside of a row
+    row_starts: Vec<Index>,
+    columns: Vec<Index>,
+    values: Vec<Scalar>,
+}
+
+impl<Scalar> SparseRowMatrix<Scalar>
+where
+    Scalar: Numerical,
+{
+    // entries are (row, column, value), repeated positions are summed
+    pub fn from_entries(dimension: Index, mut entries: Vec<(Index, Index, Scalar)>) -> Self {
+        entries.sort_by_key(|(row, column, _)| (*row, *column));
+
+        let mut row_starts = vec![0; dimension + 1];
+        let mut columns: Vec<Index> = Vec::with_capacity(entries.len());
+        let mut values: Vec<Scalar> = Vec::with_capacity(entries.len());
+        let mut last_position = None;
+
+        for (row, column, value) in entries {
+            assert!(row < dimension && column < dimension);
+
+            if last_position == Some((row, column)) {
+                let last = values.len() - 1;
+                values[last] = values[last] + value;
+            } else {
+                row_starts[row + 1] += 1;
+                columns.push(column);
+                values.push(value);
+                last_position = Some((row, column));
+            }
+        }
+
+        for row in 0..dimension {
+            row_starts[row + 1] += row_starts[row];
+        }
+
+        Self {
+            dimension,
+            row_starts,
+            columns,
+            values,
+        }
+    }
+
+    // keeps only the non zero elements of the matrix
+    pub fn from_matrix(matrix: &impl MatrixRef<Scalar = Scalar>) -> Self {
+        let dimension = matrix.dimension();
+        let mut entries = vec![];
+        for row in 0..dimension {
+            for column in 0..dimension {
+                let value = matrix.at(row, column);
+                if value != Scalar::zero() {
+                    entries.push((row, column, value));
+                }
+            }
+        }
+        Self::from_entries(dimension, entries)
+    }
+}
+
+impl<Scalar> SparseRowMatrix<Scalar> {
+    pub fn non_zeros(&self) -> Index {
+        self.values.len()
+    }
+
+    // (columns, values) of the stored elements of the row
+    pub fn row_entries(&self, row: Index) -> (&[Index], &[Scalar]) {
+        let range = self.row_starts[row]..self.row_starts[row + 1];
+        (&self.columns[range.clone()], &self.values[range])
+    }
+
+    // stored elements as (row, column, value) in row major order
+    pub fn entries(&self) -> impl Iterator<Item = (Index, Index, &Scalar)> {
+        (0..self.dimension).flat_map(move |row| {
+            let (columns, values) = self.row_entries(row);
+            columns
+                .iter()
+                .zip(values)
+                .map(move |(column, value)| (row, *column, value))
+        })
+    }
+}
+
+impl<Scalar> MatrixRef for SparseRowMatrix<Scalar>
+where
+    Scalar: Clone + OtherNumericalOps,
+{
+    type Scalar = Scalar;
+
+    fn dimension(&self) -> Index {
+        self.dimension
+    }
+
+    fn at(&self, row: Index, column: Index) -> Scalar {
+        let (columns, values) = self.row_entries(row);
+        match columns.binary_search(&column) {
+            Ok(position) => values[position].clone(),
+            Err(_) => Scalar::zero(),
+        }
+    }
+}
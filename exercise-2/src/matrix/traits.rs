@@ -7,10 +7,28 @@ pub trait MatrixRef {
 
     fn dimension(&self) -> Index;
     fn at(&self, row: Index, column: Index) -> Self::Scalar;
+
+    // contiguous storage of the row, if the matrix has one
+    fn row_slice(&self, _row: Index) -> Option<&[Self::Scalar]> {
+        None
+    }
+
+    // contiguous storage of the column, if the matrix has one
+    fn column_slice(&self, _column: Index) -> Option<&[Self::Scalar]> {
+        None
+    }
 }
 
 pub trait MatrixMutRef: MatrixRef {
     fn at_mut(&mut self, row: Index, column: Index) -> &mut Self::Scalar;
+
+    fn row_slice_mut(&mut self, _row: Index) -> Option<&mut [Self::Scalar]> {
+        None
+    }
+
+    fn column_slice_mut(&mut self, _column: Index) -> Option<&mut [Self::Scalar]> {
+        None
+    }
 }
 
 pub trait MatrixFuncInitializer: Sized + MatrixRef {
@@ -82,6 +100,14 @@ where
     fn at(&self, row: Index, column: Index) -> Impl::Scalar {
         self.represent().at(row, column)
     }
+
+    fn row_slice(&self, row: Index) -> Option<&[Impl::Scalar]> {
+        self.represent().row_slice(row)
+    }
+
+    fn column_slice(&self, column: Index) -> Option<&[Impl::Scalar]> {
+        self.represent().column_slice(column)
+    }
 }
 
 impl<Stored, Impl> MatrixMutRef for Representation<Stored, Impl>
@@ -93,6 +119,14 @@ where
     fn at_mut(&mut self, row: Index, column: Index) -> &mut Impl::Scalar {
         self.represent_mut().at_mut(row, column)
     }
+
+    fn row_slice_mut(&mut self, row: Index) -> Option<&mut [Impl::Scalar]> {
+        self.represent_mut().row_slice_mut(row)
+    }
+
+    fn column_slice_mut(&mut self, column: Index) -> Option<&mut [Impl::Scalar]> {
+        self.represent_mut().column_slice_mut(column)
+    }
 }
 
 impl<Stored, Impl> MatrixFuncInitializer for Representation<Stored, Impl>
@@ -20,6 +20,16 @@ impl<Matrix> From<Matrix> for MatrixTranspose<Matrix> {
     }
 }
 
+impl<Matrix> MatrixTranspose<Matrix> {
+    pub fn inner(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn inner_mut(&mut self) -> &mut Matrix {
+        &mut self.matrix
+    }
+}
+
 impl<Matrix> MatrixRef for MatrixTranspose<Matrix>
 where
     Matrix: MatrixRef,
@@ -33,6 +43,14 @@ where
     fn at(&self, row: Index, column: Index) -> Matrix::Scalar {
         self.matrix.at(column, row)
     }
+
+    fn row_slice(&self, row: Index) -> Option<&[Matrix::Scalar]> {
+        self.matrix.column_slice(row)
+    }
+
+    fn column_slice(&self, column: Index) -> Option<&[Matrix::Scalar]> {
+        self.matrix.row_slice(column)
+    }
 }
 
 impl<Matrix> MatrixMutRef for MatrixTranspose<Matrix>
@@ -42,6 +60,14 @@ where
     fn at_mut(&mut self, row: Index, column: Index) -> &mut Matrix::Scalar {
         self.matrix.at_mut(column, row)
     }
+
+    fn row_slice_mut(&mut self, row: Index) -> Option<&mut [Matrix::Scalar]> {
+        self.matrix.column_slice_mut(row)
+    }
+
+    fn column_slice_mut(&mut self, column: Index) -> Option<&mut [Matrix::Scalar]> {
+        self.matrix.row_slice_mut(column)
+    }
 }
 
 impl<Matrix> MatrixFuncInitializer for MatrixTranspose<Matrix>
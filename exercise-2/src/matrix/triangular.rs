@@ -0,0 +1,275 @@
+use crate::{
+    basic::{Index, Numerical, OtherNumericalOps},
+    representation::repr_ref,
+};
+
+use super::{
+    column::{dot_slices, ColumnMut},
+    traits::MatrixRef,
+    transpose::MatrixTranspose,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Diagonal {
+    // the diagonal of the matrix is used
+    Stored,
+    // the diagonal is taken to be ones, the stored one is never read
+    Unit,
+}
+
+// Represents a lazy upper triangular accessor, elements under the diagonal
+// are zero and never read from the matrix
+#[derive(Clone, Debug)]
+pub struct UpperTriangular<Matrix> {
+    matrix: Matrix,
+    diagonal: Diagonal,
+}
+
+// Represents a lazy lower triangular accessor, elements above the diagonal
+// are zero and never read from the matrix
+#[derive(Clone, Debug)]
+pub struct LowerTriangular<Matrix> {
+    matrix: Matrix,
+    diagonal: Diagonal,
+}
+
+macro_rules! impl_triangular {
+    ($name:ident, $is_zero:expr) => {
+        impl<Matrix> $name<Matrix> {
+            pub fn new(matrix: Matrix) -> Self {
+                Self::with_diagonal(matrix, Diagonal::Stored)
+            }
+
+            pub fn unit(matrix: Matrix) -> Self {
+                Self::with_diagonal(matrix, Diagonal::Unit)
+            }
+
+            pub fn with_diagonal(matrix: Matrix, diagonal: Diagonal) -> Self {
+                Self { matrix, diagonal }
+            }
+
+            pub fn diagonal(&self) -> Diagonal {
+                self.diagonal
+            }
+
+            pub fn inner(&self) -> &Matrix {
+                &self.matrix
+            }
+
+            pub fn inner_mut(&mut self) -> &mut Matrix {
+                &mut self.matrix
+            }
+        }
+
+        impl<Matrix> MatrixRef for $name<Matrix>
+        where
+            Matrix: MatrixRef,
+            Matrix::Scalar: OtherNumericalOps,
+        {
+            type Scalar = Matrix::Scalar;
+
+            fn dimension(&self) -> Index {
+                self.matrix.dimension()
+            }
+
+            fn at(&self, row: Index, column: Index) -> Self::Scalar {
+                let is_zero: fn(Index, Index) -> bool = $is_zero;
+                if is_zero(row, column) {
+                    Self::Scalar::zero()
+                } else if row == column && self.diagonal == Diagonal::Unit {
+                    Self::Scalar::one()
+                } else {
+                    self.matrix.at(row, column)
+                }
+            }
+        }
+    };
+}
+
+impl_triangular!(UpperTriangular, |row, column| row > column);
+impl_triangular!(LowerTriangular, |row, column| row < column);
+
+// Triangular systems solved in place by substitution
+pub trait TriangularSolve: MatrixRef {
+    // solves T x = b, b is replaced by x
+    fn solve<Column>(&self, vector: &mut Column)
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>;
+
+    // solves T^T x = b, b is replaced by x
+    fn solve_transposed<Column>(&self, vector: &mut Column)
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>;
+
+    fn solve_many<Column>(&self, vectors: &mut [Column])
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>,
+    {
+        for vector in vectors.iter_mut() {
+            self.solve(vector);
+        }
+    }
+
+    fn solve_transposed_many<Column>(&self, vectors: &mut [Column])
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>,
+    {
+        for vector in vectors.iter_mut() {
+            self.solve_transposed(vector);
+        }
+    }
+}
+
+impl<Matrix> TriangularSolve for UpperTriangular<Matrix>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+{
+    fn solve<Column>(&self, vector: &mut Column)
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>,
+    {
+        back_substitution(&self.matrix, self.diagonal, vector);
+    }
+
+    fn solve_transposed<Column>(&self, vector: &mut Column)
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>,
+    {
+        forward_substitution(
+            &MatrixTranspose::from(repr_ref(&self.matrix)),
+            self.diagonal,
+            vector,
+        );
+    }
+}
+
+impl<Matrix> TriangularSolve for LowerTriangular<Matrix>
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+{
+    fn solve<Column>(&self, vector: &mut Column)
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>,
+    {
+        forward_substitution(&self.matrix, self.diagonal, vector);
+    }
+
+    fn solve_transposed<Column>(&self, vector: &mut Column)
+    where
+        Column: ColumnMut<Scalar = Self::Scalar>,
+    {
+        back_substitution(
+            &MatrixTranspose::from(repr_ref(&self.matrix)),
+            self.diagonal,
+            vector,
+        );
+    }
+}
+
+// solves the upper triangle of the matrix, elements under the diagonal are
+// not read
+fn back_substitution<Matrix, Column>(matrix: &Matrix, diagonal: Diagonal, vector: &mut Column)
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    Column: ColumnMut<Scalar = Matrix::Scalar>,
+{
+    let dimension = matrix.dimension();
+    let unit = diagonal == Diagonal::Unit;
+
+    if let Some(values) = vector.as_mut_slice() {
+        // column major over contiguous columns
+        let columns: Option<Vec<&[Matrix::Scalar]>> =
+            (0..dimension).map(|i| matrix.column_slice(i)).collect();
+        if let Some(columns) = columns {
+            for i in (0..dimension).rev() {
+                if !unit {
+                    values[i] /= columns[i][i];
+                }
+                let value = values[i];
+                for (x, a) in values[..i].iter_mut().zip(&columns[i][..i]) {
+                    *x -= *a * value;
+                }
+            }
+            return;
+        }
+
+        // row major over contiguous rows
+        let rows: Option<Vec<&[Matrix::Scalar]>> =
+            (0..dimension).map(|i| matrix.row_slice(i)).collect();
+        if let Some(rows) = rows {
+            for i in (0..dimension).rev() {
+                values[i] -= dot_slices(&rows[i][i + 1..], &values[i + 1..]);
+                if !unit {
+                    values[i] /= rows[i][i];
+                }
+            }
+            return;
+        }
+    }
+
+    // column major
+    for i in (0..dimension).rev() {
+        if !unit {
+            *vector.at_mut(i) = vector.at(i) / matrix.at(i, i);
+        }
+        for j in (0..i).rev() {
+            *vector.at_mut(j) = vector.at(j) - matrix.at(j, i) * vector.at(i);
+        }
+    }
+}
+
+// solves the lower triangle of the matrix, elements above the diagonal are
+// not read
+fn forward_substitution<Matrix, Column>(matrix: &Matrix, diagonal: Diagonal, vector: &mut Column)
+where
+    Matrix: MatrixRef,
+    Matrix::Scalar: Numerical,
+    Column: ColumnMut<Scalar = Matrix::Scalar>,
+{
+    let dimension = matrix.dimension();
+    let unit = diagonal == Diagonal::Unit;
+
+    if let Some(values) = vector.as_mut_slice() {
+        // column major over contiguous columns
+        let columns: Option<Vec<&[Matrix::Scalar]>> =
+            (0..dimension).map(|i| matrix.column_slice(i)).collect();
+        if let Some(columns) = columns {
+            for i in 0..dimension {
+                if !unit {
+                    values[i] /= columns[i][i];
+                }
+                let value = values[i];
+                for (x, a) in values[i + 1..].iter_mut().zip(&columns[i][i + 1..]) {
+                    *x -= *a * value;
+                }
+            }
+            return;
+        }
+
+        // row major over contiguous rows
+        let rows: Option<Vec<&[Matrix::Scalar]>> =
+            (0..dimension).map(|i| matrix.row_slice(i)).collect();
+        if let Some(rows) = rows {
+            for i in 0..dimension {
+                values[i] -= dot_slices(&rows[i][..i], &values[..i]);
+                if !unit {
+                    values[i] /= rows[i][i];
+                }
+            }
+            return;
+        }
+    }
+
+    // column major
+    for i in 0..dimension {
+        if !unit {
+            *vector.at_mut(i) = vector.at(i) / matrix.at(i, i);
+        }
+        for j in i + 1..dimension {
+            *vector.at_mut(j) = vector.at(j) - matrix.at(j, i) * vector.at(i);
+        }
+    }
+}
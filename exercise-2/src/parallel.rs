@@ -0,0 +1,115 @@
+use std::thread;
+
+use crate::{
+    basic::{Index, Numerical, OtherNumericalOps},
+    matrix::{
+        column::{apply_at, dot, ColumnFuncInitializer, ColumnOf, ColumnRef},
+        traits::{MatrixFuncInitializer, MatrixRef},
+    },
+    representation::repr_ref,
+};
+
+// Number of scoped threads used by the parallel kernels. Every kernel splits
+// its work into independent rows or columns and performs exactly the same
+// floating point operations as the serial version, so the results do not
+// depend on the number of threads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parallelism {
+    threads: usize,
+}
+
+impl Parallelism {
+    pub fn new(threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+        }
+    }
+
+    pub fn available() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, |threads| threads.get()))
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    // calls work(index, item) for every item, splitting items into contiguous
+    // chunks, one per thread
+    pub(crate) fn for_each_mut<T, F>(&self, items: &mut [T], work: F)
+    where
+        T: Send,
+        F: Fn(Index, &mut T) + Sync,
+    {
+        if self.threads == 1 || items.len() < 2 {
+            for (index, item) in items.iter_mut().enumerate() {
+                work(index, item);
+            }
+            return;
+        }
+
+        let chunk_size = items.len().div_ceil(self.threads);
+        let work = &work;
+
+        thread::scope(|scope| {
+            for (chunk_index, chunk) in items.chunks_mut(chunk_size).enumerate() {
+                scope.spawn(move || {
+                    for (offset, item) in chunk.iter_mut().enumerate() {
+                        work(chunk_index * chunk_size + offset, item);
+                    }
+                });
+            }
+        });
+    }
+}
+
+impl Default for Parallelism {
+    fn default() -> Self {
+        Self::available()
+    }
+}
+
+// Parallel version of column::apply, rows are partitioned between threads
+pub fn parallel_apply<Matrix, ColumnIn, ColumnOut>(
+    matrix: &Matrix,
+    column: &ColumnIn,
+    parallelism: Parallelism,
+) -> ColumnOut
+where
+    Matrix: MatrixRef + Sync,
+    Matrix::Scalar: Numerical + Send,
+    ColumnIn: ColumnRef<Scalar = Matrix::Scalar> + Sync,
+    ColumnOut: ColumnFuncInitializer + ColumnRef<Scalar = Matrix::Scalar>,
+{
+    let mut values = vec![Matrix::Scalar::zero(); matrix.dimension()];
+    parallelism.for_each_mut(&mut values, |row, value| {
+        *value = apply_at(matrix, column, row);
+    });
+    ColumnOut::new_func(values.len(), |i| values[i])
+}
+
+// Parallel version of MatrixSymmetricSquare::symmetric_square, rows of the
+// result are partitioned between threads
+pub fn parallel_symmetric_square<Matrix, OutMatrix>(
+    matrix: &Matrix,
+    parallelism: Parallelism,
+) -> OutMatrix
+where
+    Matrix: MatrixRef + Sync,
+    Matrix::Scalar: Numerical + Send,
+    OutMatrix: MatrixFuncInitializer + MatrixRef<Scalar = Matrix::Scalar>,
+{
+    let dimension = matrix.dimension();
+    let mut values = vec![Matrix::Scalar::zero(); dimension * dimension];
+
+    let mut rows: Vec<&mut [Matrix::Scalar]> = values.chunks_mut(dimension).collect();
+    parallelism.for_each_mut(&mut rows, |i, row| {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = dot(
+                &ColumnOf::new(repr_ref::<Matrix>(matrix), i),
+                &ColumnOf::new(repr_ref::<Matrix>(matrix), j),
+            );
+        }
+    });
+
+    OutMatrix::new_func(dimension, |i, j| values[i * dimension + j])
+}
@@ -0,0 +1,253 @@
+use std::{
+    fmt::{self, Display},
+    io::{self, Read, Write},
+};
+
+use crate::basic::Index;
+
+// Binary format of a stored factorisation, integers and scalars are little
+// endian:
+//
+//   magic       8 bytes  "NMFACTOR"
+//   version     u32      FORMAT_VERSION
+//   kind        u8       1 for LU, 2 for QR
+//   scalar      u8       size of the scalar in bytes, 4 for f32, 8 for f64
+//   dimension   u64      n > 0
+//
+// followed for LU by
+//
+//   upper       n*n scalars, L'A row major (rows are not permuted back)
+//   inverse     n u64, inverse_permutation
+//   eta         n*(n-1) scalars, eta vectors one after another
+//
+// and for QR by
+//
+//   upper       n*n scalars, R row major
+//   hausdorf    n*(n-1) scalars, normed hausdorf vectors one after another
+
+pub const MAGIC: &[u8; 8] = b"NMFACTOR";
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FactorisationKind {
+    LU,
+    QR,
+}
+
+impl FactorisationKind {
+    fn tag(&self) -> u8 {
+        match self {
+            FactorisationKind::LU => 1,
+            FactorisationKind::QR => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            1 => Some(FactorisationKind::LU),
+            2 => Some(FactorisationKind::QR),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FactorisationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FactorisationKind::LU => write!(f, "LU"),
+            FactorisationKind::QR => write!(f, "QR"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PersistenceError {
+    Io(io::Error),
+    InvalidMagic,
+    UnsupportedVersion {
+        version: u32,
+    },
+    UnknownKind {
+        tag: u8,
+    },
+    KindMismatch {
+        expected: FactorisationKind,
+        found: FactorisationKind,
+    },
+    ScalarMismatch {
+        expected: u8,
+        found: u8,
+    },
+    InvalidDimension {
+        dimension: u64,
+    },
+    InvalidPermutation,
+}
+
+impl Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io(error) => write!(f, "io error: {error}"),
+            PersistenceError::InvalidMagic => write!(f, "not a stored factorisation"),
+            PersistenceError::UnsupportedVersion { version } => write!(
+                f,
+                "unsupported format version {version}, at most {FORMAT_VERSION} is supported"
+            ),
+            PersistenceError::UnknownKind { tag } => write!(f, "unknown factorisation kind {tag}"),
+            PersistenceError::KindMismatch { expected, found } => {
+                write!(f, "expected {expected} factorisation, found {found}")
+            }
+            PersistenceError::ScalarMismatch { expected, found } => write!(
+                f,
+                "expected scalars of {expected} bytes, found scalars of {found} bytes"
+            ),
+            PersistenceError::InvalidDimension { dimension } => {
+                write!(f, "invalid dimension {dimension}")
+            }
+            PersistenceError::InvalidPermutation => write!(f, "permutation is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+impl From<io::Error> for PersistenceError {
+    fn from(error: io::Error) -> Self {
+        PersistenceError::Io(error)
+    }
+}
+
+// Scalars that can be stored in the binary format
+pub trait PersistentScalar: Sized {
+    const SIZE: u8;
+
+    fn write_le(&self, writer: &mut impl Write) -> io::Result<()>;
+    fn read_le(reader: &mut impl Read) -> io::Result<Self>;
+}
+
+macro_rules! impl_persistent_scalar {
+    ($ty:ident) => {
+        impl PersistentScalar for $ty {
+            const SIZE: u8 = std::mem::size_of::<$ty>() as u8;
+
+            fn write_le(&self, writer: &mut impl Write) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn read_le(reader: &mut impl Read) -> io::Result<Self> {
+                let mut bytes = [0; std::mem::size_of::<$ty>()];
+                reader.read_exact(&mut bytes)?;
+                Ok($ty::from_le_bytes(bytes))
+            }
+        }
+    };
+}
+
+impl_persistent_scalar!(f32);
+impl_persistent_scalar!(f64);
+
+pub(crate) fn write_header<Scalar>(
+    writer: &mut impl Write,
+    kind: FactorisationKind,
+    dimension: Index,
+) -> io::Result<()>
+where
+    Scalar: PersistentScalar,
+{
+    writer.write_all(MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&[kind.tag(), Scalar::SIZE])?;
+    writer.write_all(&(dimension as u64).to_le_bytes())
+}
+
+// checks the header and returns the dimension
+pub(crate) fn read_header<Scalar>(
+    reader: &mut impl Read,
+    kind: FactorisationKind,
+) -> Result<Index, PersistenceError>
+where
+    Scalar: PersistentScalar,
+{
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(PersistenceError::InvalidMagic);
+    }
+
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version == 0 || version > FORMAT_VERSION {
+        return Err(PersistenceError::UnsupportedVersion { version });
+    }
+
+    let mut tags = [0; 2];
+    reader.read_exact(&mut tags)?;
+    let found = FactorisationKind::from_tag(tags[0])
+        .ok_or(PersistenceError::UnknownKind { tag: tags[0] })?;
+    if found != kind {
+        return Err(PersistenceError::KindMismatch {
+            expected: kind,
+            found,
+        });
+    }
+    if tags[1] != Scalar::SIZE {
+        return Err(PersistenceError::ScalarMismatch {
+            expected: Scalar::SIZE,
+            found: tags[1],
+        });
+    }
+
+    let mut dimension = [0; 8];
+    reader.read_exact(&mut dimension)?;
+    let dimension = u64::from_le_bytes(dimension);
+    match Index::try_from(dimension) {
+        Ok(valid) if valid > 0 && valid.checked_mul(valid).is_some() => Ok(valid),
+        _ => Err(PersistenceError::InvalidDimension { dimension }),
+    }
+}
+
+pub(crate) fn write_scalars<Scalar>(writer: &mut impl Write, values: &[Scalar]) -> io::Result<()>
+where
+    Scalar: PersistentScalar,
+{
+    values.iter().try_for_each(|value| value.write_le(writer))
+}
+
+// values are read one by one, so a corrupted length fails on the end of the
+// input instead of allocating
+pub(crate) fn read_scalars<Scalar>(reader: &mut impl Read, count: Index) -> io::Result<Vec<Scalar>>
+where
+    Scalar: PersistentScalar,
+{
+    (0..count).map(|_| Scalar::read_le(reader)).collect()
+}
+
+pub(crate) fn write_permutation(writer: &mut impl Write, permutation: &[Index]) -> io::Result<()> {
+    permutation
+        .iter()
+        .try_for_each(|index| writer.write_all(&(*index as u64).to_le_bytes()))
+}
+
+// checks that every index of 0..dimension appears exactly once
+pub(crate) fn read_permutation(
+    reader: &mut impl Read,
+    dimension: Index,
+) -> Result<Vec<Index>, PersistenceError> {
+    let mut seen = vec![false; dimension];
+    (0..dimension)
+        .map(|_| {
+            let mut bytes = [0; 8];
+            reader.read_exact(&mut bytes)?;
+            let index = Index::try_from(u64::from_le_bytes(bytes))
+                .map_err(|_| PersistenceError::InvalidPermutation)?;
+            match seen.get_mut(index) {
+                Some(seen) if !*seen => {
+                    *seen = true;
+                    Ok(index)
+                }
+                _ => Err(PersistenceError::InvalidPermutation),
+            }
+        })
+        .collect()
+}
@@ -1,8 +1,21 @@
-use std::mem::swap;
+use std::{
+    io::{Read, Write},
+    mem::swap,
+};
 
 use crate::{
-    basic::{Numerical, OtherNumericalOps},
-    matrix::{column::ColumnMut, solve_upper::solve_upper, traits::MatrixMutRef},
+    basic::{Index, Numerical, OtherNumericalOps},
+    matrix::{
+        column::{dot_slices, ColumnMut},
+        dense::DenseRowMatrix,
+        solve_upper::solve_upper,
+        traits::{MatrixFuncInitializer, MatrixMutRef, MatrixRef},
+    },
+    parallel::Parallelism,
+    persistence::{
+        read_header, read_scalars, write_header, write_scalars, FactorisationKind,
+        PersistenceError, PersistentScalar,
+    },
 };
 
 fn sqrt<S>(value: S) -> S
@@ -12,6 +25,145 @@ where
     value.sqrt_trait()
 }
 
+// zeroes the column under the diagonal and stores the normed hausdorf vector
+// of the reflection
+fn reflect_column<Matrix>(
+    matrix: &mut Matrix,
+    column: Index,
+    hausdorf_vectors: &mut [Matrix::Scalar],
+) where
+    Matrix: MatrixMutRef,
+    Matrix::Scalar: Numerical,
+{
+    let dimension = matrix.dimension();
+    let length: Matrix::Scalar = sqrt(
+        (column..dimension)
+            .map(|row| matrix.at(row, column) * matrix.at(row, column))
+            .sum(),
+    );
+    // reflecting to the opposite sign of the diagonal avoids cancellation in
+    // the hausdorf vector, which is zero for an already reduced column otherwise
+    let length = if matrix.at(column, column) > Matrix::Scalar::zero() {
+        -length
+    } else {
+        length
+    };
+
+    // update the column and fill the hausdorf vector
+    for row in column..dimension {
+        swap(
+            &mut hausdorf_vectors[column * dimension + row],
+            matrix.at_mut(row, column),
+        );
+    }
+    *matrix.at_mut(column, column) = length;
+    hausdorf_vectors[column * dimension + column] -= length;
+
+    let hausdorf_length = sqrt(
+        (column..dimension)
+            .map(|row| {
+                hausdorf_vectors[column * dimension + row]
+                    * hausdorf_vectors[column * dimension + row]
+            })
+            .sum(),
+    );
+
+    // zero column, the reflection is left as identity
+    if hausdorf_length == Matrix::Scalar::zero() {
+        return;
+    }
+
+    for row in column..dimension {
+        hausdorf_vectors[column * dimension + row] /= hausdorf_length;
+    }
+}
+
+// applies the reflection to one column right of the reflected one by at
+fn reflect_column_at<Matrix>(
+    matrix: &mut Matrix,
+    column: Index,
+    affected_column: Index,
+    hausdorf_vector: &[Matrix::Scalar],
+) where
+    Matrix: MatrixMutRef,
+    Matrix::Scalar: Numerical,
+{
+    let dimension = matrix.dimension();
+    let dot_product = (column..dimension)
+        .map(|i| hausdorf_vector[i] * matrix.at(i, affected_column))
+        .sum();
+    for (row, hausdorf_value) in hausdorf_vector.iter().enumerate().skip(column) {
+        *matrix.at_mut(row, affected_column) -=
+            (*hausdorf_value * dot_product) * Matrix::Scalar::from(2);
+    }
+}
+
+// applies the reflection to the columns right of the reflected one
+fn reflect_rest<Matrix>(matrix: &mut Matrix, column: Index, hausdorf_vector: &[Matrix::Scalar])
+where
+    Matrix: MatrixMutRef,
+    Matrix::Scalar: Numerical,
+{
+    let dimension = matrix.dimension();
+    let hausdorf_tail = &hausdorf_vector[column..];
+
+    // column major over contiguous columns, a view may expose the columns
+    // read only, these are updated by at
+    if matrix.column_slice(column).is_some() {
+        for affected_column in column + 1..dimension {
+            match matrix.column_slice_mut(affected_column) {
+                Some(column_data) => {
+                    let dot_product = dot_slices(hausdorf_tail, &column_data[column..]);
+                    for (value, hausdorf_value) in
+                        column_data[column..].iter_mut().zip(hausdorf_tail)
+                    {
+                        *value -= (*hausdorf_value * dot_product) * Matrix::Scalar::from(2);
+                    }
+                }
+                None => reflect_column_at(matrix, column, affected_column, hausdorf_vector),
+            }
+        }
+        return;
+    }
+
+    // row major over contiguous rows, dot products are accumulated row by row
+    let rows: Option<Vec<&[Matrix::Scalar]>> = (column..dimension)
+        .map(|row| matrix.row_slice(row))
+        .collect();
+    if let Some(rows) = rows {
+        let mut dot_products = vec![Matrix::Scalar::zero(); dimension - column - 1];
+        for (row_data, hausdorf_value) in rows.iter().zip(hausdorf_tail) {
+            for (dot_product, value) in dot_products.iter_mut().zip(&row_data[column + 1..]) {
+                *dot_product = *dot_product + *hausdorf_value * *value;
+            }
+        }
+
+        for (row, hausdorf_value) in (column..dimension).zip(hausdorf_tail) {
+            let update = |dot_product: &Matrix::Scalar| {
+                (*hausdorf_value * *dot_product) * Matrix::Scalar::from(2)
+            };
+            match matrix.row_slice_mut(row) {
+                Some(row_data) => {
+                    for (value, dot_product) in row_data[column + 1..].iter_mut().zip(&dot_products)
+                    {
+                        *value -= update(dot_product);
+                    }
+                }
+                None => {
+                    for (offset, dot_product) in dot_products.iter().enumerate() {
+                        *matrix.at_mut(row, column + 1 + offset) -= update(dot_product);
+                    }
+                }
+            }
+        }
+        return;
+    }
+
+    for affected_column in column + 1..dimension {
+        reflect_column_at(matrix, column, affected_column, hausdorf_vector);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct QRDecomposition<Matrix>
 where
@@ -34,48 +186,12 @@ where
         let mut hausdorf_vectors = vec![Matrix::Scalar::zero(); dimension * (dimension - 1)];
 
         for column in 0..dimension - 1 {
-            let length = sqrt(
-                (column..dimension)
-                    .map(|row| matrix.at(row, column) * matrix.at(row, column))
-                    .sum(),
-            );
-
-            // update the column and fill the hausdorf vector
-            for row in column..dimension {
-                swap(
-                    &mut hausdorf_vectors[column * dimension + row],
-                    matrix.at_mut(row, column),
-                );
-            }
-            *matrix.at_mut(column, column) = length;
-            hausdorf_vectors[column * dimension + column] -= length;
-
-            let hausdorf_length = sqrt(
-                (column..dimension)
-                    .map(|row| {
-                        hausdorf_vectors[column * dimension + row]
-                            * hausdorf_vectors[column * dimension + row]
-                    })
-                    .sum(),
+            reflect_column(&mut matrix, column, &mut hausdorf_vectors);
+            reflect_rest(
+                &mut matrix,
+                column,
+                &hausdorf_vectors[column * dimension..(column + 1) * dimension],
             );
-
-            for row in column..dimension {
-                hausdorf_vectors[column * dimension + row] /= hausdorf_length;
-            }
-
-            // updating the rest of the matrix
-            for affected_column in column + 1..dimension {
-                let dot_product = (column..dimension)
-                    .map(|i| {
-                        hausdorf_vectors[column * dimension + i] * matrix.at(i, affected_column)
-                    })
-                    .sum();
-                for row in column..dimension {
-                    *matrix.at_mut(row, affected_column) -=
-                        (hausdorf_vectors[column * dimension + row] * dot_product)
-                            * Matrix::Scalar::from(2);
-                }
-            }
         }
 
         Self {
@@ -91,14 +207,27 @@ where
         let dimension = self.upper.dimension();
 
         // applying Q to vector
-        for hausdorf_index in 0..dimension - 1 {
-            let dot_product = (hausdorf_index..dimension)
-                .map(|i| self.hausdorf_vectors[hausdorf_index * dimension + i] * vector.at(i))
-                .sum();
-            for row in hausdorf_index..dimension {
-                *vector.at_mut(row) -= (self.hausdorf_vectors[hausdorf_index * dimension + row]
-                    * dot_product)
-                    * Matrix::Scalar::from(2);
+        if let Some(values) = vector.as_mut_slice() {
+            for hausdorf_index in 0..dimension - 1 {
+                let hausdorf_tail = &self.hausdorf_vectors
+                    [hausdorf_index * dimension + hausdorf_index..(hausdorf_index + 1) * dimension];
+                let dot_product = dot_slices(hausdorf_tail, &values[hausdorf_index..]);
+                for (value, hausdorf_value) in
+                    values[hausdorf_index..].iter_mut().zip(hausdorf_tail)
+                {
+                    *value -= (*hausdorf_value * dot_product) * Matrix::Scalar::from(2);
+                }
+            }
+        } else {
+            for hausdorf_index in 0..dimension - 1 {
+                let dot_product = (hausdorf_index..dimension)
+                    .map(|i| self.hausdorf_vectors[hausdorf_index * dimension + i] * vector.at(i))
+                    .sum();
+                for row in hausdorf_index..dimension {
+                    *vector.at_mut(row) -=
+                        (self.hausdorf_vectors[hausdorf_index * dimension + row] * dot_product)
+                            * Matrix::Scalar::from(2);
+                }
             }
         }
 
@@ -106,3 +235,79 @@ where
         solve_upper(&self.upper, vector);
     }
 }
+
+impl<Matrix> QRDecomposition<Matrix>
+where
+    Matrix: MatrixMutRef + MatrixFuncInitializer,
+    Matrix::Scalar: Numerical + PersistentScalar,
+{
+    // Stores the factorisation in the binary format of persistence
+    pub fn write(&self, mut writer: impl Write) -> Result<(), PersistenceError> {
+        let dimension = self.upper.dimension();
+        let upper: Vec<Matrix::Scalar> = (0..dimension * dimension)
+            .map(|i| self.upper.at(i / dimension, i % dimension))
+            .collect();
+
+        write_header::<Matrix::Scalar>(&mut writer, FactorisationKind::QR, dimension)?;
+        write_scalars(&mut writer, &upper)?;
+        write_scalars(&mut writer, &self.hausdorf_vectors)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read(mut reader: impl Read) -> Result<QRDecomposition<Matrix>, PersistenceError> {
+        let dimension = read_header::<Matrix::Scalar>(&mut reader, FactorisationKind::QR)?;
+        let upper: Vec<Matrix::Scalar> = read_scalars(&mut reader, dimension * dimension)?;
+        let hausdorf_vectors = read_scalars(&mut reader, dimension * (dimension - 1))?;
+
+        Ok(QRDecomposition {
+            upper: Matrix::new_func(dimension, |row, column| upper[row * dimension + column]),
+            hausdorf_vectors,
+        })
+    }
+}
+
+impl<Scalar> QRDecomposition<DenseRowMatrix<Scalar>>
+where
+    Scalar: Numerical + Send + Sync,
+{
+    // Same reflections as calculate, the dot products are partitioned between
+    // threads by columns and the updates by rows
+    pub fn calculate_parallel(
+        mut matrix: DenseRowMatrix<Scalar>,
+        parallelism: Parallelism,
+    ) -> QRDecomposition<DenseRowMatrix<Scalar>> {
+        let dimension = matrix.dimension();
+        let mut hausdorf_vectors = vec![Scalar::zero(); dimension * (dimension - 1)];
+
+        for column in 0..dimension - 1 {
+            reflect_column(&mut matrix, column, &mut hausdorf_vectors);
+            let hausdorf_vector = &hausdorf_vectors[column * dimension..(column + 1) * dimension];
+
+            let mut dot_products = vec![Scalar::zero(); dimension - column - 1];
+            parallelism.for_each_mut(&mut dot_products, |offset, dot_product| {
+                let affected_column = column + 1 + offset;
+                *dot_product = (column..dimension)
+                    .map(|i| hausdorf_vector[i] * matrix.at(i, affected_column))
+                    .sum();
+            });
+
+            let mut rows: Vec<&mut [Scalar]> = matrix
+                .data_mut()
+                .chunks_mut(dimension)
+                .skip(column)
+                .collect();
+            parallelism.for_each_mut(&mut rows, |offset, row_data| {
+                let hausdorf_value = hausdorf_vector[column + offset];
+                for (value, dot_product) in row_data[column + 1..].iter_mut().zip(&dot_products) {
+                    *value -= (hausdorf_value * *dot_product) * Scalar::from(2);
+                }
+            });
+        }
+
+        Self {
+            upper: matrix,
+            hausdorf_vectors,
+        }
+    }
+}
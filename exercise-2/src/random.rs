@@ -0,0 +1,50 @@
+use crate::basic::Index;
+
+// Small seeded pseudo random generator (xorshift64*), the same seed gives the
+// same sequence on every platform
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64 step, so that close seeds give unrelated sequences and
+        // the state is never zero
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        Self {
+            state: if z == 0 { 0x9e37_79b9_7f4a_7c15 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // uniform in [0, 1)
+    pub fn next_scalar(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // uniform in [start, end)
+    pub fn uniform(&mut self, start: f64, end: f64) -> f64 {
+        start + (end - start) * self.next_scalar()
+    }
+
+    // uniform in 0..end
+    pub fn index(&mut self, end: Index) -> Index {
+        (self.next_u64() % end as u64) as Index
+    }
+
+    // uniform integer in start..=end
+    pub fn integer(&mut self, start: i64, end: i64) -> i64 {
+        start + (self.next_u64() % (end - start + 1) as u64) as i64
+    }
+}
@@ -1,6 +1,8 @@
 use std::{
     borrow::{Borrow, BorrowMut},
     marker::PhantomData,
+    rc::Rc,
+    sync::Arc,
 };
 
 // An owned version of Borrow and BorrowMut
@@ -45,3 +47,66 @@ pub fn repr_ref<'a, T>(v: &'a T) -> RefRepresentation<'a, T> {
 pub fn repr_mut<'a, T>(v: &'a mut T) -> MutRefRepresentation<'a, T> {
     MutRefRepresentation::from(v)
 }
+
+// Shared ownership, several views and decompositions can hold the same
+// matrix without cloning it
+pub type RcRepresentation<T> = Representation<Rc<T>, T>;
+pub type ArcRepresentation<T> = Representation<Arc<T>, T>;
+
+pub fn repr_rc<T>(v: &Rc<T>) -> RcRepresentation<T> {
+    RcRepresentation::from(Rc::clone(v))
+}
+
+pub fn repr_arc<T>(v: &Arc<T>) -> ArcRepresentation<T> {
+    ArcRepresentation::from(Arc::clone(v))
+}
+
+// Shared storage that is cloned on the first mutable borrow if anything else
+// still holds it, the unique owner mutates in place
+#[derive(Clone, Debug)]
+pub struct CopyOnWrite<Pointer> {
+    pointer: Pointer,
+}
+
+impl<Pointer> CopyOnWrite<Pointer> {
+    pub fn new(pointer: Pointer) -> Self {
+        Self { pointer }
+    }
+
+    pub fn into_inner(self) -> Pointer {
+        self.pointer
+    }
+}
+
+macro_rules! impl_copy_on_write {
+    ($pointer:ident) => {
+        impl<T> Borrow<T> for CopyOnWrite<$pointer<T>> {
+            fn borrow(&self) -> &T {
+                &self.pointer
+            }
+        }
+
+        impl<T> BorrowMut<T> for CopyOnWrite<$pointer<T>>
+        where
+            T: Clone,
+        {
+            fn borrow_mut(&mut self) -> &mut T {
+                $pointer::make_mut(&mut self.pointer)
+            }
+        }
+    };
+}
+
+impl_copy_on_write!(Rc);
+impl_copy_on_write!(Arc);
+
+pub type CowRepresentation<T> = Representation<CopyOnWrite<Rc<T>>, T>;
+pub type ArcCowRepresentation<T> = Representation<CopyOnWrite<Arc<T>>, T>;
+
+pub fn repr_cow<T>(v: &Rc<T>) -> CowRepresentation<T> {
+    CowRepresentation::from(CopyOnWrite::new(Rc::clone(v)))
+}
+
+pub fn repr_arc_cow<T>(v: &Arc<T>) -> ArcCowRepresentation<T> {
+    ArcCowRepresentation::from(CopyOnWrite::new(Arc::clone(v)))
+}
@@ -0,0 +1,278 @@
+use std::{fs, io::Cursor, rc::Rc};
+
+use exercise_2::{
+    basic::Index,
+    iterative_methods::{simple_iterative_solve, zeidel_iterative_solve},
+    matrix::{
+        column::{apply_at, ColumnFunc},
+        dense::DenseRowMatrix,
+        market,
+        norms::NormedColumn,
+        traits::MatrixRef,
+    },
+    test::Scalar,
+};
+
+use crate::{solve_lu, solve_qr};
+
+pub const SOLVE_USAGE: &str = "solve --matrix <file> --vector <file> \
+    [--method lu|qr|simple|seidel] [--accuracy <e>] [--format csv|json]";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Lu,
+    Qr,
+    Simple,
+    Zeidel,
+}
+
+impl Method {
+    fn name(&self) -> &'static str {
+        match self {
+            Method::Lu => "lu",
+            Method::Qr => "qr",
+            Method::Simple => "simple",
+            Method::Zeidel => "seidel",
+        }
+    }
+
+    fn is_iterative(&self) -> bool {
+        matches!(self, Method::Simple | Method::Zeidel)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+struct SolveOptions {
+    matrix_path: String,
+    vector_path: String,
+    method: Method,
+    accuracy: Scalar,
+    format: OutputFormat,
+}
+
+#[derive(Debug, Clone)]
+struct Solution {
+    answer: Vec<Scalar>,
+    residual: Scalar,
+    iterations: Option<Index>,
+}
+
+fn parse_options(args: &[String]) -> Result<SolveOptions, String> {
+    let mut matrix_path = None;
+    let mut vector_path = None;
+    let mut method = Method::Lu;
+    let mut accuracy: Scalar = 1e-6;
+    let mut format = OutputFormat::Csv;
+
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {option}"))?;
+
+        match option.as_str() {
+            "--matrix" => matrix_path = Some(value.clone()),
+            "--vector" => vector_path = Some(value.clone()),
+            "--method" => {
+                method = match value.as_str() {
+                    "lu" => Method::Lu,
+                    "qr" => Method::Qr,
+                    "simple" => Method::Simple,
+                    "seidel" | "zeidel" => Method::Zeidel,
+                    _ => return Err(format!("unknown method {value}")),
+                }
+            }
+            "--accuracy" => {
+                accuracy = value
+                    .parse()
+                    .map_err(|_| format!("invalid accuracy {value}"))?;
+                if accuracy.is_nan() || accuracy <= 0.0 {
+                    return Err(format!("accuracy has to be positive, got {value}"));
+                }
+            }
+            "--format" => {
+                format = match value.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    _ => return Err(format!("unknown format {value}")),
+                }
+            }
+            _ => return Err(format!("unknown option {option}")),
+        }
+    }
+
+    Ok(SolveOptions {
+        matrix_path: matrix_path.ok_or("missing --matrix")?,
+        vector_path: vector_path.ok_or("missing --vector")?,
+        method,
+        accuracy,
+        format,
+    })
+}
+
+fn is_matrix_market(content: &str) -> bool {
+    content.trim_start().starts_with("%%MatrixMarket")
+}
+
+// rows of values separated by ';' or ','
+fn parse_csv(path: &str, content: &str) -> Result<Vec<Vec<Scalar>>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(number, line)| {
+            line.split([';', ','])
+                .map(|value| {
+                    value.trim().parse::<Scalar>().map_err(|_| {
+                        format!("{path}:{}: invalid value \"{}\"", number + 1, value.trim())
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn read_matrix(path: &str) -> Result<DenseRowMatrix<Scalar>, String> {
+    let content = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+
+    if is_matrix_market(&content) {
+        return market::read_dense(Cursor::new(content))
+            .map_err(|error| format!("{path}: {error}"));
+    }
+
+    let rows = parse_csv(path, &content)?;
+    let dimension = rows.len();
+    if let Some((row, values)) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, values)| values.len() != dimension)
+    {
+        return Err(format!(
+            "{path}: row {} has {} values, expected {dimension}",
+            row + 1,
+            values.len()
+        ));
+    }
+
+    Ok(DenseRowMatrix::new(dimension, rows.concat()))
+}
+
+fn read_vector(path: &str) -> Result<Vec<Scalar>, String> {
+    let content = fs::read_to_string(path).map_err(|error| format!("{path}: {error}"))?;
+
+    if is_matrix_market(&content) {
+        return market::read_vector(Cursor::new(content))
+            .map_err(|error| format!("{path}: {error}"));
+    }
+
+    // either a single row or a single column
+    let rows = parse_csv(path, &content)?;
+    match rows.as_slice() {
+        [row] => Ok(row.clone()),
+        _ if rows.iter().all(|values| values.len() == 1) => Ok(rows.concat()),
+        _ => Err(format!("{path}: expected a single row or a single column")),
+    }
+}
+
+fn solve(options: &SolveOptions) -> Result<Solution, String> {
+    let matrix = Rc::new(read_matrix(&options.matrix_path)?);
+    let vector = read_vector(&options.vector_path)?;
+
+    let dimension = matrix.dimension();
+    if dimension == 0 {
+        return Err(format!("{}: matrix is empty", options.matrix_path));
+    }
+    if vector.len() != dimension {
+        return Err(format!(
+            "matrix has dimension {dimension}, but the vector has {} values",
+            vector.len()
+        ));
+    }
+
+    let (answer, iterations) = match options.method {
+        Method::Lu => (solve_lu(&matrix, &vector), None),
+        Method::Qr => (solve_qr(&matrix, &vector), None),
+        Method::Simple => {
+            let (answer, steps) =
+                simple_iterative_solve(matrix.as_ref(), &vector, options.accuracy);
+            (answer, Some(steps))
+        }
+        Method::Zeidel => {
+            let (answer, steps) =
+                zeidel_iterative_solve(matrix.as_ref(), &vector, options.accuracy);
+            (answer, Some(steps))
+        }
+    };
+
+    let residual = ColumnFunc::new(dimension, |i| {
+        apply_at(matrix.as_ref(), &answer, i) - vector[i]
+    })
+    .norm_one();
+
+    Ok(Solution {
+        answer,
+        residual,
+        iterations,
+    })
+}
+
+fn json_number(value: Scalar) -> String {
+    if value.is_finite() {
+        format!("{value:?}")
+    } else {
+        String::from("null")
+    }
+}
+
+fn print_solution(options: &SolveOptions, solution: &Solution) {
+    let method = options.method.name();
+    let Solution {
+        answer,
+        residual,
+        iterations,
+    } = solution;
+
+    match options.format {
+        OutputFormat::Csv => {
+            let accuracy = if options.method.is_iterative() {
+                format!("{:.e}", options.accuracy)
+            } else {
+                String::new()
+            };
+            let iterations = iterations.map_or(String::new(), |k| k.to_string());
+
+            println!("method;e;k;residual;x");
+            println!("{method};{accuracy};{iterations};{residual:.e};{answer:?}");
+        }
+        OutputFormat::Json => {
+            let accuracy = if options.method.is_iterative() {
+                json_number(options.accuracy)
+            } else {
+                String::from("null")
+            };
+            let iterations = iterations.map_or(String::from("null"), |k| k.to_string());
+            let answer: Vec<String> = answer.iter().map(|x| json_number(*x)).collect();
+
+            println!("{{");
+            println!("  \"method\": \"{method}\",");
+            println!("  \"accuracy\": {accuracy},");
+            println!("  \"iterations\": {iterations},");
+            println!("  \"residual\": {},", json_number(*residual));
+            println!("  \"x\": [{}]", answer.join(", "));
+            println!("}}");
+        }
+    }
+}
+
+pub fn solve_command(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args)?;
+    let solution = solve(&options)?;
+    print_solution(&options, &solution);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::basic::Numerical;
+
+// How the terms of dot products, norms and polynomials are accumulated, chosen
+// per call as a type parameter: dot_with::<Neumaier, _, _>(&x, &y)
+pub trait Summation {
+    fn sum<S: Numerical>(terms: impl IntoIterator<Item = S>) -> S;
+
+    // the sum of rhs_i lhs_i over the shorter slice, a method that splits
+    // the terms can split the slices instead of collecting the products
+    fn sum_products<S: Numerical>(rhs: &[S], lhs: &[S]) -> S {
+        Self::sum(rhs.iter().zip(lhs).map(|(r, l)| *r * *l))
+    }
+}
+
+// left to right, the error grows with the number of terms
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Naive;
+
+// the rounding error of every addition is carried into the next term, the
+// error does not grow with the number of terms unless a term is larger than
+// the sum so far
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Kahan;
+
+// Kahan with the error taken from the smaller of the sum and the term, so
+// large terms cancelling each other keep the small ones
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Neumaier;
+
+// the halves are summed separately, the error grows with the logarithm of the
+// number of terms
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pairwise;
+
+impl Summation for Naive {
+    fn sum<S: Numerical>(terms: impl IntoIterator<Item = S>) -> S {
+        terms.into_iter().sum()
+    }
+}
+
+impl Summation for Kahan {
+    fn sum<S: Numerical>(terms: impl IntoIterator<Item = S>) -> S {
+        let mut sum = S::zero();
+        let mut compensation = S::zero();
+
+        for term in terms {
+            let corrected = term - compensation;
+            let next = sum + corrected;
+            compensation = (next - sum) - corrected;
+            sum = next;
+        }
+
+        sum
+    }
+}
+
+impl Summation for Neumaier {
+    fn sum<S: Numerical>(terms: impl IntoIterator<Item = S>) -> S {
+        let mut sum = S::zero();
+        let mut compensation = S::zero();
+
+        for term in terms {
+            let next = sum + term;
+            compensation = compensation
+                + if sum.abs_trait() >= term.abs_trait() {
+                    (sum - next) + term
+                } else {
+                    (term - next) + sum
+                };
+            sum = next;
+        }
+
+        sum + compensation
+    }
+}
+
+// blocks this short are summed left to right
+const PAIRWISE_BLOCK: usize = 8;
+
+fn pairwise_slice<S: Numerical>(terms: &[S]) -> S {
+    if terms.len() <= PAIRWISE_BLOCK {
+        return terms.iter().copied().sum();
+    }
+
+    let (left, right) = terms.split_at(terms.len() / 2);
+    pairwise_slice(left) + pairwise_slice(right)
+}
+
+fn pairwise_products<S: Numerical>(rhs: &[S], lhs: &[S]) -> S {
+    if rhs.len() <= PAIRWISE_BLOCK {
+        return rhs.iter().zip(lhs).map(|(r, l)| *r * *l).sum();
+    }
+
+    let (rhs_left, rhs_right) = rhs.split_at(rhs.len() / 2);
+    let (lhs_left, lhs_right) = lhs.split_at(rhs.len() / 2);
+    pairwise_products(rhs_left, lhs_left) + pairwise_products(rhs_right, lhs_right)
+}
+
+impl Summation for Pairwise {
+    // an iterator has to be collected to be split
+    fn sum<S: Numerical>(terms: impl IntoIterator<Item = S>) -> S {
+        let terms: Vec<S> = terms.into_iter().collect();
+        pairwise_slice(&terms)
+    }
+
+    fn sum_products<S: Numerical>(rhs: &[S], lhs: &[S]) -> S {
+        let length = rhs.len().min(lhs.len());
+        pairwise_products(&rhs[..length], &lhs[..length])
+    }
+}
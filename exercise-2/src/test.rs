@@ -1,11 +1,15 @@
+use std::rc::Rc;
+
 use crate::{
     basic::Index,
     matrix::{
-        column::ColumnFunc,
+        column::{apply_with, ColumnFunc},
         dense::DenseRowMatrix,
         norms::NormedColumn,
         traits::{MatrixFuncInitializer, MatrixRef},
     },
+    random::Random,
+    summation::Neumaier,
 };
 
 pub type Scalar = f64;
@@ -13,7 +17,7 @@ pub type Scalar = f64;
 #[derive(Debug, Clone)]
 pub struct TestCase {
     pub name: String,
-    pub matrix: DenseRowMatrix<Scalar>,
+    pub matrix: Rc<DenseRowMatrix<Scalar>>,
     pub vector: Vec<Scalar>,
     pub answer: Vec<Scalar>,
 }
@@ -33,7 +37,7 @@ pub struct IterativeTestResult {
 
 pub fn on_case(
     case: &TestCase,
-    implementation: impl Fn(&DenseRowMatrix<Scalar>, &Vec<Scalar>) -> Vec<Scalar>,
+    implementation: impl Fn(&Rc<DenseRowMatrix<Scalar>>, &Vec<Scalar>) -> Vec<Scalar>,
 ) -> TestResult {
     let dimension = case.matrix.dimension();
     let impl_answer = implementation(&case.matrix, &case.vector);
@@ -65,7 +69,7 @@ pub fn create_fifth_case(epsilon: Scalar, dimension: Index) -> TestCase {
 
     TestCase {
         name: format!("5;{};{:e}", dimension, epsilon),
-        matrix,
+        matrix: Rc::new(matrix),
         answer,
         vector,
     }
@@ -88,36 +92,247 @@ pub fn create_static_test_cases() -> Vec<TestCase> {
     vec![
         TestCase {
             name: String::from("0"),
-            matrix: DenseRowMatrix::new(3, vec![0.0, 2.0, 3.0, 1.0, 2.0, 4.0, 4.0, 5.0, 6.0]),
+            matrix: Rc::new(DenseRowMatrix::new(
+                3,
+                vec![0.0, 2.0, 3.0, 1.0, 2.0, 4.0, 4.0, 5.0, 6.0],
+            )),
             answer: vec![1.0, 2.0, 3.0],
             vector: vec![13.0, 17.0, 32.0],
         },
         TestCase {
             name: String::from("1"),
-            matrix: DenseRowMatrix::new(3, vec![8.0, 1.0, 1.0, 1.0, 10.0, 1.0, 1.0, 1.0, 12.0]),
+            matrix: Rc::new(DenseRowMatrix::new(
+                3,
+                vec![8.0, 1.0, 1.0, 1.0, 10.0, 1.0, 1.0, 1.0, 12.0],
+            )),
             vector: vec![10.0, 12.0, 14.0],
             answer: vec![1.0, 1.0, 1.0],
         },
         TestCase {
             name: String::from("2"),
-            matrix: DenseRowMatrix::new(3, vec![-8.0, 1.0, 1.0, 1.0, -10.0, 1.0, 1.0, 1.0, -12.0]),
+            matrix: Rc::new(DenseRowMatrix::new(
+                3,
+                vec![-8.0, 1.0, 1.0, 1.0, -10.0, 1.0, 1.0, 1.0, -12.0],
+            )),
             vector: vec![-10.0, -12.0, -14.0],
             answer: vec![375.0 / 232.0, 349.0 / 232.0, 331.0 / 232.0],
         },
         TestCase {
             name: String::from("3"),
-            matrix: DenseRowMatrix::new(
+            matrix: Rc::new(DenseRowMatrix::new(
                 3,
                 vec![-8.0, 9.0, 10.0, 11.0, -10.0, 7.0, 10.0, 11.0, -12.0],
-            ),
+            )),
             vector: vec![10.0, 12.0, 14.0],
             answer: vec![444.0 / 307.0, 358.0 / 307.0, 340.0 / 307.0],
         },
         TestCase {
             name: String::from("4"),
-            matrix: DenseRowMatrix::new(3, vec![8.0, 7.0, 7.0, 7.0, 10.0, 7.0, 7.0, 7.0, 12.0]),
+            matrix: Rc::new(DenseRowMatrix::new(
+                3,
+                vec![8.0, 7.0, 7.0, 7.0, 10.0, 7.0, 7.0, 7.0, 12.0],
+            )),
             vector: vec![10.0, 12.0, 14.0],
             answer: vec![-1.0 / 44.0, 29.0 / 44.0, 35.0 / 44.0],
         },
     ]
 }
+
+// b=Ax with Neumaier compensated summation, so that for answers with exactly
+// representable products b is correctly rounded up to a couple of ulps
+fn compensated_apply(matrix: &DenseRowMatrix<Scalar>, answer: &[Scalar]) -> Vec<Scalar> {
+    apply_with::<Neumaier, _, _, _>(matrix, &answer.to_vec())
+}
+
+// exact while the intermediate products fit into the mantissa, close to the
+// rounding error afterwards
+fn binomial(n: Index, k: Index) -> Scalar {
+    (0..k).fold(1.0, |value, i| {
+        value * ((n - i) as Scalar) / ((i + 1) as Scalar)
+    })
+}
+
+// H_ij = 1/(i+j+1), b = e_1 so that the answer is the first column of the
+// inverse, which has integer entries
+// (H^-1)_i1 = (-1)^(i+1) i C(n+i-1, n-1) C(n, i) for 1-based i
+// The answer is exact for the real Hilbert matrix, the stored one is rounded
+pub fn create_hilbert_case(dimension: Index) -> TestCase {
+    let matrix = DenseRowMatrix::new_func(dimension, |i, j| 1.0 / ((i + j + 1) as Scalar));
+
+    let mut vector = vec![0.0; dimension];
+    vector[0] = 1.0;
+
+    let n = dimension;
+    let answer = (1..=n)
+        .map(|i| {
+            let value = (i as Scalar) * binomial(n + i - 1, n - 1) * binomial(n, i);
+            if i % 2 == 1 {
+                value
+            } else {
+                -value
+            }
+        })
+        .collect();
+
+    TestCase {
+        name: format!("hilbert;{};", dimension),
+        matrix: Rc::new(matrix),
+        vector,
+        answer,
+    }
+}
+
+// V_ij = t_i^j with equally spaced nodes on [-1, 1], answer is all ones
+pub fn create_vandermonde_case(dimension: Index) -> TestCase {
+    let node = |i: Index| {
+        if dimension == 1 {
+            0.0
+        } else {
+            -1.0 + 2.0 * (i as Scalar) / ((dimension - 1) as Scalar)
+        }
+    };
+    let matrix = DenseRowMatrix::new_func(dimension, |i, j| node(i).powi(j as i32));
+    let answer = vec![1.0; dimension];
+    let vector = compensated_apply(&matrix, &answer);
+
+    TestCase {
+        name: format!("vandermonde;{};", dimension),
+        matrix: Rc::new(matrix),
+        vector,
+        answer,
+    }
+}
+
+// A = Q D Q^T with a random householder reflection Q and eigenvalues spread
+// geometrically from 1 to 1/condition, so cond_2(A) = condition
+pub fn create_spd_case(dimension: Index, condition: Scalar, seed: u64) -> TestCase {
+    let mut random = Random::new(seed);
+
+    let direction: Vec<Scalar> = (0..dimension).map(|_| random.uniform(-1.0, 1.0)).collect();
+    let direction_square: Scalar = direction.iter().map(|v| v * v).sum();
+    let q = |i: Index, j: Index| {
+        let identity = if i == j { 1.0 } else { 0.0 };
+        identity - 2.0 * direction[i] * direction[j] / direction_square
+    };
+    let eigenvalue = |k: Index| {
+        if dimension == 1 {
+            1.0
+        } else {
+            condition.powf(-(k as Scalar) / ((dimension - 1) as Scalar))
+        }
+    };
+
+    let matrix = DenseRowMatrix::new_func(dimension, |i, j| {
+        (0..dimension)
+            .map(|k| q(i, k) * eigenvalue(k) * q(j, k))
+            .sum()
+    });
+    let answer: Vec<Scalar> = (0..dimension).map(|_| random.uniform(-1.0, 1.0)).collect();
+    let vector = compensated_apply(&matrix, &answer);
+
+    TestCase {
+        name: format!("spd;{};{:e}", dimension, condition),
+        matrix: Rc::new(matrix),
+        vector,
+        answer,
+    }
+}
+
+// random integer matrix with a_ii > sum_j |a_ij| and an integer answer, so
+// b is exact
+pub fn create_diagonally_dominant_case(dimension: Index, seed: u64) -> TestCase {
+    let mut random = Random::new(seed);
+
+    let mut data: Vec<Scalar> = (0..dimension * dimension)
+        .map(|_| random.integer(-5, 5) as Scalar)
+        .collect();
+    for i in 0..dimension {
+        let off_diagonal: Scalar = (0..dimension)
+            .filter(|j| *j != i)
+            .map(|j| data[i * dimension + j].abs())
+            .sum();
+        data[i * dimension + i] = off_diagonal + random.integer(1, 5) as Scalar;
+    }
+
+    let matrix = DenseRowMatrix::new(dimension, data);
+    let answer: Vec<Scalar> = (0..dimension)
+        .map(|_| random.integer(-5, 5) as Scalar)
+        .collect();
+    let vector = compensated_apply(&matrix, &answer);
+
+    TestCase {
+        name: format!("dominant;{};", dimension),
+        matrix: Rc::new(matrix),
+        vector,
+        answer,
+    }
+}
+
+// Upper triangular K = diag(1, s, ..., s^(n-1)) (I - c N) with N being all
+// ones above the diagonal and c^2 + s^2 = 1, answer is all ones
+pub fn create_kahan_case(dimension: Index, theta: Scalar) -> TestCase {
+    let (s, c) = theta.sin_cos();
+    let matrix = DenseRowMatrix::new_func(dimension, |i, j| {
+        let scale = s.powi(i as i32);
+        if i == j {
+            scale
+        } else if i < j {
+            -c * scale
+        } else {
+            0.0
+        }
+    });
+    let answer = vec![1.0; dimension];
+    let vector = compensated_apply(&matrix, &answer);
+
+    TestCase {
+        name: format!("kahan;{};{}", dimension, theta),
+        matrix: Rc::new(matrix),
+        vector,
+        answer,
+    }
+}
+
+// tridiag(-1, 2, -1), the discrete -u''=2 with zero boundary values, whose
+// solution x_i = (i+1)(n-i) is exact in integers
+pub fn create_poisson_case(dimension: Index) -> TestCase {
+    let matrix = DenseRowMatrix::new_func(dimension, |i, j| {
+        if i == j {
+            2.0
+        } else if i.abs_diff(j) == 1 {
+            -1.0
+        } else {
+            0.0
+        }
+    });
+    let answer = (0..dimension)
+        .map(|i| ((i + 1) * (dimension - i)) as Scalar)
+        .collect();
+    let vector = vec![2.0; dimension];
+
+    TestCase {
+        name: format!("poisson;{};", dimension),
+        matrix: Rc::new(matrix),
+        vector,
+        answer,
+    }
+}
+
+// every generator family for every dimension
+pub fn create_generated_cases(dimensions: Vec<Index>, seed: u64) -> Vec<TestCase> {
+    dimensions
+        .into_iter()
+        .flat_map(|dimension| {
+            let seed = seed.wrapping_add(dimension as u64);
+            vec![
+                create_hilbert_case(dimension),
+                create_vandermonde_case(dimension),
+                create_spd_case(dimension, 1e3, seed),
+                create_spd_case(dimension, 1e8, seed),
+                create_diagonally_dominant_case(dimension, seed),
+                create_kahan_case(dimension, 1.2),
+                create_poisson_case(dimension),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,160 @@
+use exercise_2::matrix::{
+    column::apply,
+    dense::DenseRowMatrix,
+    norms::NormedColumn,
+    traits::{MatrixFuncInitializer, MatrixMutRef, MatrixRef},
+};
+
+use crate::{
+    basic::Scalar,
+    jacobian::FiniteDifference,
+    newton_method_n::decompose_jacobian,
+    solver::{NewtonOptions, NewtonReport, NewtonResult, Termination},
+};
+
+// How the approximation H of the inverse jacobian is corrected after the step
+// s = x_new - x changed f by y = f(x_new) - f(x). Both make H y = s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BroydenUpdate {
+    // the jacobian changes least, H += (s - H y) s^T H / (s^T H y)
+    Good,
+    // the inverse changes least, H += (s - H y) y^T / (y^T y)
+    Bad,
+}
+
+// the inverse by columns, None when the jacobian is singular
+fn inverse(jacobian: DenseRowMatrix<Scalar>) -> Option<DenseRowMatrix<Scalar>> {
+    let dimension = jacobian.dimension();
+    let decomposition = decompose_jacobian(jacobian)?;
+
+    let columns: Vec<Vec<Scalar>> = (0..dimension)
+        .map(|column| {
+            let mut unit = vec![0.0; dimension];
+            unit[column] = 1.0;
+            decomposition.solve(&mut unit);
+            unit
+        })
+        .collect();
+    Some(DenseRowMatrix::new_func(dimension, |row, column| {
+        columns[column][row]
+    }))
+}
+
+fn dot(x: &[Scalar], y: &[Scalar]) -> Scalar {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+// Quasi-Newton steps x_new = x - H f(x) with rank one corrections of H
+// instead of a new jacobian, f is evaluated once per iteration
+pub fn broyden_method(
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    jacobian: DenseRowMatrix<Scalar>,
+    update: BroydenUpdate,
+    mut x: Vec<Scalar>,
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
+    let dimension = x.len();
+    let report = |root, iterations, residual, step, termination| {
+        NewtonReport {
+            root,
+            iterations,
+            residual,
+            step,
+            termination,
+        }
+        .into_result()
+    };
+
+    let mut fx = f(&x);
+    let mut step = Scalar::INFINITY;
+    let Some(mut h) = inverse(jacobian) else {
+        return report(x, 0, fx.norm_inf(), step, Termination::SingularJacobian);
+    };
+
+    for k in 0..options.max_iterations {
+        if options.trace {
+            println!("k={k}");
+        }
+        let h_fx: Vec<Scalar> = apply(&h, &fx);
+        let s: Vec<Scalar> = h_fx.iter().map(|v| -v).collect();
+        // the report keeps the last finite step
+        if !s.norm_inf().is_finite() {
+            return report(x, k, fx.norm_inf(), step, Termination::NotFinite);
+        }
+        step = s.norm_inf();
+
+        let x_new: Vec<Scalar> = x.iter().zip(&s).map(|(x, s)| x + s).collect();
+        let fx_new = f(&x_new);
+
+        if options.trace {
+            println!("New x = {x_new:?}, residue {fx_new:?}");
+        }
+
+        if step < options.accuracy {
+            let residual = fx_new.norm_inf();
+            return report(x_new, k + 1, residual, step, Termination::Step);
+        }
+
+        // H += (s - H y) w^T / (w^T y) with w = H^T s or w = y
+        let y: Vec<Scalar> = fx_new.iter().zip(&fx).map(|(new, old)| new - old).collect();
+        let h_y: Vec<Scalar> = apply(&h, &y);
+        let w: Vec<Scalar> = match update {
+            BroydenUpdate::Good => (0..dimension)
+                .map(|j| (0..dimension).map(|i| s[i] * h.at(i, j)).sum())
+                .collect(),
+            BroydenUpdate::Bad => y.clone(),
+        };
+        // the update breaks down when w and y are orthogonal up to rounding
+        let denominator = dot(&w, &y);
+        let scale = dot(&w, &w).sqrt() * dot(&y, &y).sqrt();
+        if !denominator.is_finite() || denominator.abs() <= Scalar::EPSILON * scale {
+            return report(
+                x_new,
+                k + 1,
+                fx_new.norm_inf(),
+                step,
+                Termination::Breakdown,
+            );
+        }
+
+        for (i, (s, h_y)) in s.iter().zip(&h_y).enumerate() {
+            let factor = (s - h_y) / denominator;
+            for (j, w) in w.iter().enumerate() {
+                *h.at_mut(i, j) += factor * w;
+            }
+        }
+
+        x = x_new;
+        fx = fx_new;
+    }
+
+    let residual = fx.norm_inf();
+    report(
+        x,
+        options.max_iterations,
+        residual,
+        step,
+        Termination::MaxIterations,
+    )
+}
+
+// broyden_method from the finite difference jacobian at x
+pub fn broyden_method_differences(
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    differences: &FiniteDifference,
+    update: BroydenUpdate,
+    x: Vec<Scalar>,
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
+    match differences.jacobian(&f, &x) {
+        Ok(jacobian) => broyden_method(f, jacobian, update, x, options),
+        Err(_) => NewtonReport {
+            residual: f(&x).norm_inf(),
+            root: x,
+            iterations: 0,
+            step: Scalar::INFINITY,
+            termination: Termination::PatternMismatch,
+        }
+        .into_result(),
+    }
+}
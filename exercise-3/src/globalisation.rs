@@ -0,0 +1,294 @@
+use exercise_2::matrix::{
+    column::apply, dense::DenseRowMatrix, norms::NormedColumn, traits::MatrixRef,
+};
+
+use crate::{
+    basic::Scalar,
+    newton_method_n::decompose_jacobian,
+    solver::{NewtonOptions, NewtonReport, NewtonResult, Termination},
+};
+
+// Newton steps d = -J^-1 F damped by t = 1, 1/2, 1/4, ... until the Armijo
+// condition |F(x + t d)|^2 <= (1 - 2 c t) |F(x)|^2 holds
+#[derive(Clone, Debug)]
+pub struct LineSearch {
+    // c, the part of the decrease predicted by the linearisation to reach
+    pub sufficient_decrease: Scalar,
+    // the search fails when t gets smaller
+    pub min_damping: Scalar,
+}
+
+impl Default for LineSearch {
+    fn default() -> Self {
+        Self {
+            sufficient_decrease: 1e-4,
+            min_damping: 1e-10,
+        }
+    }
+}
+
+// Dogleg steps within a radius, the radius shrinks when |F|^2 decreases much
+// less than the linearisation predicts and grows when the prediction is good
+#[derive(Clone, Debug)]
+pub struct TrustRegion {
+    pub initial_radius: Scalar,
+    pub max_radius: Scalar,
+    // steps with a smaller ratio of the actual and the predicted decrease are
+    // not taken
+    pub min_ratio: Scalar,
+}
+
+impl Default for TrustRegion {
+    fn default() -> Self {
+        Self {
+            initial_radius: 1.0,
+            max_radius: 1e3,
+            min_ratio: 1e-4,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Globalisation {
+    LineSearch(LineSearch),
+    TrustRegion(TrustRegion),
+}
+
+fn dot(x: &[Scalar], y: &[Scalar]) -> Scalar {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+fn scaled(t: Scalar, x: &[Scalar]) -> Vec<Scalar> {
+    x.iter().map(|x| t * x).collect()
+}
+
+// x + t y
+fn add_scaled(x: &[Scalar], t: Scalar, y: &[Scalar]) -> Vec<Scalar> {
+    x.iter().zip(y).map(|(x, y)| x + t * y).collect()
+}
+
+// the result of a globalised method with the lengths of the damped or the
+// dogleg steps taken, the infinity norm of each
+#[derive(Clone, Debug)]
+pub struct GlobalisedResult {
+    pub result: NewtonResult<Vec<Scalar>>,
+    pub steps: Vec<Scalar>,
+}
+
+fn report(
+    root: Vec<Scalar>,
+    iterations: usize,
+    fx: &[Scalar],
+    steps: Vec<Scalar>,
+    termination: Termination,
+) -> GlobalisedResult {
+    let result = NewtonReport {
+        root,
+        iterations,
+        residual: fx.iter().map(|v| v.abs()).fold(0.0, Scalar::max),
+        step: steps.last().copied().unwrap_or(Scalar::INFINITY),
+        termination,
+    }
+    .into_result();
+    GlobalisedResult { result, steps }
+}
+
+// The iterations stop when the full Newton step is shorter than the accuracy,
+// the last step taken is then the full one
+pub fn newton_method_n_globalised(
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    fprime: impl Fn(&[Scalar]) -> DenseRowMatrix<Scalar>,
+    x: Vec<Scalar>,
+    globalisation: &Globalisation,
+    options: &NewtonOptions,
+) -> GlobalisedResult {
+    match globalisation {
+        Globalisation::LineSearch(line_search) => {
+            newton_line_search(f, fprime, x, line_search, options)
+        }
+        Globalisation::TrustRegion(trust_region) => {
+            newton_trust_region(f, fprime, x, trust_region, options)
+        }
+    }
+}
+
+fn newton_line_search(
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    fprime: impl Fn(&[Scalar]) -> DenseRowMatrix<Scalar>,
+    mut x: Vec<Scalar>,
+    line_search: &LineSearch,
+    options: &NewtonOptions,
+) -> GlobalisedResult {
+    let mut fx = f(&x);
+    let mut steps = vec![];
+
+    for k in 0..options.max_iterations {
+        if options.trace {
+            println!("k={k}");
+        }
+        let Some(decomposition) = decompose_jacobian(fprime(&x)) else {
+            return report(x, k, &fx, steps, Termination::SingularJacobian);
+        };
+        // -d
+        let mut x_diff = fx.clone();
+        decomposition.solve(&mut x_diff);
+        let full_step = x_diff.norm_inf();
+        if !full_step.is_finite() {
+            return report(x, k, &fx, steps, Termination::NotFinite);
+        }
+
+        if full_step < options.accuracy {
+            let x_new = add_scaled(&x, -1.0, &x_diff);
+            let fx_new = f(&x_new);
+            steps.push(full_step);
+            return report(x_new, k + 1, &fx_new, steps, Termination::Step);
+        }
+
+        let merit = dot(&fx, &fx);
+        let mut damping = 1.0;
+        let (x_new, fx_new) = loop {
+            let x_new = add_scaled(&x, -damping, &x_diff);
+            let fx_new = f(&x_new);
+            let threshold = (1.0 - 2.0 * line_search.sufficient_decrease * damping) * merit;
+            if dot(&fx_new, &fx_new) <= threshold {
+                break (x_new, fx_new);
+            }
+
+            damping /= 2.0;
+            if damping < line_search.min_damping {
+                return report(x, k, &fx, steps, Termination::NoDecrease);
+            }
+        };
+
+        if options.trace {
+            println!("t = {damping}, new x = {x_new:?}, residue {fx_new:?}");
+        }
+        steps.push(damping * full_step);
+        x = x_new;
+        fx = fx_new;
+    }
+
+    report(
+        x,
+        options.max_iterations,
+        &fx,
+        steps,
+        Termination::MaxIterations,
+    )
+}
+
+// the point of the path from the steepest descent step to the Newton step
+// at the radius
+fn dogleg(
+    jacobian: &DenseRowMatrix<Scalar>,
+    fx: &[Scalar],
+    newton: &[Scalar],
+    radius: Scalar,
+) -> Vec<Scalar> {
+    if dot(newton, newton).sqrt() <= radius {
+        return newton.to_vec();
+    }
+
+    // the gradient of |F|^2 / 2 is J^T F, the model |F + J p|^2 is least
+    // along it at the cauchy point
+    let dimension = fx.len();
+    let gradient: Vec<Scalar> = (0..dimension)
+        .map(|j| (0..dimension).map(|i| jacobian.at(i, j) * fx[i]).sum())
+        .collect();
+    let j_gradient: Vec<Scalar> = apply(jacobian, &gradient);
+    let gradient_square = dot(&gradient, &gradient);
+    let cauchy = scaled(-gradient_square / dot(&j_gradient, &j_gradient), &gradient);
+
+    let cauchy_length = dot(&cauchy, &cauchy).sqrt();
+    if cauchy_length >= radius {
+        return scaled(-radius / gradient_square.sqrt(), &gradient);
+    }
+
+    // |cauchy + tau (newton - cauchy)| = radius for tau in [0, 1]
+    let direction = add_scaled(newton, -1.0, &cauchy);
+    let a = dot(&direction, &direction);
+    let b = dot(&cauchy, &direction);
+    let c = cauchy_length * cauchy_length - radius * radius;
+    let tau = (-b + (b * b - a * c).sqrt()) / a;
+    add_scaled(&cauchy, tau, &direction)
+}
+
+fn newton_trust_region(
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    fprime: impl Fn(&[Scalar]) -> DenseRowMatrix<Scalar>,
+    mut x: Vec<Scalar>,
+    trust_region: &TrustRegion,
+    options: &NewtonOptions,
+) -> GlobalisedResult {
+    let mut fx = f(&x);
+    let mut steps = vec![];
+    let mut radius = trust_region.initial_radius;
+
+    for k in 0..options.max_iterations {
+        if options.trace {
+            println!("k={k}, radius={radius}");
+        }
+        let jacobian = fprime(&x);
+        let Some(decomposition) = decompose_jacobian(jacobian.clone()) else {
+            return report(x, k, &fx, steps, Termination::SingularJacobian);
+        };
+        let mut x_diff = fx.clone();
+        decomposition.solve(&mut x_diff);
+        let newton = scaled(-1.0, &x_diff);
+        let full_step = newton.norm_inf();
+        if !full_step.is_finite() {
+            return report(x, k, &fx, steps, Termination::NotFinite);
+        }
+
+        if full_step < options.accuracy {
+            let x_new = add_scaled(&x, 1.0, &newton);
+            let fx_new = f(&x_new);
+            steps.push(full_step);
+            return report(x_new, k + 1, &fx_new, steps, Termination::Step);
+        }
+
+        // a rejected step only shrinks the radius, the jacobian and the
+        // Newton step at x stay
+        let merit = dot(&fx, &fx);
+        let (step, x_new, fx_new) = loop {
+            let step = dogleg(&jacobian, &fx, &newton, radius);
+            let linearised = add_scaled(&fx, 1.0, &apply::<_, _, Vec<Scalar>>(&jacobian, &step));
+            let x_new = add_scaled(&x, 1.0, &step);
+            let fx_new = f(&x_new);
+
+            let predicted = merit - dot(&linearised, &linearised);
+            let actual = merit - dot(&fx_new, &fx_new);
+            let ratio = actual / predicted;
+
+            let length = dot(&step, &step).sqrt();
+            if ratio.is_nan() || ratio < 0.25 {
+                radius = 0.25 * length;
+            } else if ratio > 0.75 && length >= 0.99 * radius {
+                radius = (2.0 * radius).min(trust_region.max_radius);
+            }
+
+            if options.trace {
+                println!("ratio = {ratio}, step = {step:?}");
+            }
+
+            if ratio > trust_region.min_ratio {
+                break (step, x_new, fx_new);
+            }
+            if radius.is_nan() || radius <= Scalar::EPSILON * dot(&x, &x).sqrt().max(1.0) {
+                return report(x, k, &fx, steps, Termination::NoDecrease);
+            }
+        };
+
+        steps.push(step.norm_inf());
+        x = x_new;
+        fx = fx_new;
+    }
+
+    report(
+        x,
+        options.max_iterations,
+        &fx,
+        steps,
+        Termination::MaxIterations,
+    )
+}
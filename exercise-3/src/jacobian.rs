@@ -0,0 +1,231 @@
+use std::fmt::{self, Display};
+
+use exercise_2::{basic::Index, matrix::dense::DenseRowMatrix};
+
+use crate::basic::Scalar;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difference {
+    // (f(x + h) - f(x)) / h, error O(h) with h ~ sqrt(eps)
+    Forward,
+    // (f(x + h) - f(x - h)) / 2h, error O(h^2) with h ~ cbrt(eps)
+    Central,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    OutOfRange {
+        row: Index,
+        column: Index,
+        dimension: Index,
+    },
+    // the pattern is for another number of variables than x has
+    DimensionMismatch {
+        pattern: Index,
+        dimension: Index,
+    },
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::OutOfRange {
+                row,
+                column,
+                dimension,
+            } => write!(
+                f,
+                "entry ({row}, {column}) is out of range for dimension {dimension}"
+            ),
+            PatternError::DimensionMismatch { pattern, dimension } => write!(
+                f,
+                "pattern of dimension {pattern} for {dimension} variables"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+// Entries of the jacobian that can be non zero. Columns without common rows
+// are grouped, the columns of a group are perturbed by one evaluation.
+#[derive(Clone, Debug)]
+pub struct SparsityPattern {
+    // rows of the non zero entries of every column
+    columns: Vec<Vec<Index>>,
+    groups: Vec<Vec<Index>>,
+}
+
+impl SparsityPattern {
+    // (row, column) of the entries that can be non zero
+    pub fn new(dimension: Index, entries: &[(Index, Index)]) -> Result<Self, PatternError> {
+        let mut columns = vec![vec![]; dimension];
+        for &(row, column) in entries {
+            if row >= dimension || column >= dimension {
+                return Err(PatternError::OutOfRange {
+                    row,
+                    column,
+                    dimension,
+                });
+            }
+            if !columns[column].contains(&row) {
+                columns[column].push(row);
+            }
+        }
+
+        // greedy coloring, a column joins the first group none of whose
+        // columns has a row of it
+        let mut groups: Vec<Vec<Index>> = vec![];
+        let mut groups_rows: Vec<Vec<bool>> = vec![];
+        for (column, rows) in columns.iter().enumerate() {
+            let group = groups_rows
+                .iter()
+                .position(|used| rows.iter().all(|row| !used[*row]));
+            let group = match group {
+                Some(group) => group,
+                None => {
+                    groups.push(vec![]);
+                    groups_rows.push(vec![false; dimension]);
+                    groups.len() - 1
+                }
+            };
+            groups[group].push(column);
+            for row in rows {
+                groups_rows[group][*row] = true;
+            }
+        }
+
+        Ok(Self { columns, groups })
+    }
+
+    // every column in a group of its own, without the coloring
+    pub fn dense(dimension: Index) -> Self {
+        Self {
+            columns: vec![(0..dimension).collect(); dimension],
+            groups: (0..dimension).map(|column| vec![column]).collect(),
+        }
+    }
+
+    pub fn dimension(&self) -> Index {
+        self.columns.len()
+    }
+
+    // evaluations of f per jacobian are 1 + groups for forward and 2 groups
+    // for central differences
+    pub fn groups(&self) -> Index {
+        self.groups.len()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FiniteDifference {
+    pub difference: Difference,
+    // all entries are computed when there is none
+    pub pattern: Option<SparsityPattern>,
+}
+
+impl FiniteDifference {
+    pub fn new(difference: Difference) -> Self {
+        Self {
+            difference,
+            pattern: None,
+        }
+    }
+
+    pub fn with_pattern(difference: Difference, pattern: SparsityPattern) -> Self {
+        Self {
+            difference,
+            pattern: Some(pattern),
+        }
+    }
+
+    // Err when the pattern does not fit x of the dimension
+    pub fn check(&self, dimension: Index) -> Result<(), PatternError> {
+        match &self.pattern {
+            Some(pattern) if pattern.dimension() != dimension => {
+                Err(PatternError::DimensionMismatch {
+                    pattern: pattern.dimension(),
+                    dimension,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // h relative to x, not below the relative step for |x| < 1, rounded so
+    // that x + h - x is exactly h
+    fn step(&self, x: Scalar) -> Scalar {
+        let relative = match self.difference {
+            Difference::Forward => Scalar::EPSILON.sqrt(),
+            Difference::Central => Scalar::EPSILON.cbrt(),
+        };
+        let h = relative * x.abs().max(1.0);
+        (x + h) - x
+    }
+
+    pub fn jacobian(
+        &self,
+        f: &impl Fn(&[Scalar]) -> Vec<Scalar>,
+        x: &[Scalar],
+    ) -> Result<DenseRowMatrix<Scalar>, PatternError> {
+        let dimension = x.len();
+        self.check(dimension)?;
+        let steps: Vec<Scalar> = x.iter().map(|x| self.step(*x)).collect();
+        let shifted = |group: &[Index], sign: Scalar| {
+            let mut shifted = x.to_vec();
+            for column in group {
+                shifted[*column] += sign * steps[*column];
+            }
+            f(&shifted)
+        };
+
+        let fx = match self.difference {
+            Difference::Forward => Some(f(x)),
+            Difference::Central => None,
+        };
+        // f(x + h) - f(x) or f(x + h) - f(x - h) with the columns of the group
+        // shifted, the points are width h apart
+        let width = match self.difference {
+            Difference::Forward => 1.0,
+            Difference::Central => 2.0,
+        };
+        let difference = |group: &[Index]| -> Vec<Scalar> {
+            let upper = shifted(group, 1.0);
+            let lower_shifted;
+            let lower = match &fx {
+                Some(fx) => fx,
+                None => {
+                    lower_shifted = shifted(group, -1.0);
+                    &lower_shifted
+                }
+            };
+            upper.iter().zip(lower).map(|(u, l)| u - l).collect()
+        };
+
+        let mut data = vec![0.0; dimension * dimension];
+        match &self.pattern {
+            Some(pattern) => {
+                for group in pattern.groups.iter() {
+                    let difference = difference(group);
+                    for column in group {
+                        for row in pattern.columns[*column].iter() {
+                            data[row * dimension + column] =
+                                difference[*row] / (width * steps[*column]);
+                        }
+                    }
+                }
+            }
+            // the dense pattern, every column on its own
+            None => {
+                for column in 0..dimension {
+                    let difference = difference(&[column]);
+                    for (row, difference) in difference.iter().enumerate() {
+                        data[row * dimension + column] = difference / (width * steps[column]);
+                    }
+                }
+            }
+        }
+
+        Ok(DenseRowMatrix::new(dimension, data))
+    }
+}
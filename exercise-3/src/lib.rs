@@ -0,0 +1,9 @@
+pub mod basic;
+pub mod broyden;
+pub mod globalisation;
+pub mod jacobian;
+pub mod localize_root;
+pub mod newton_method;
+pub mod newton_method_n;
+pub mod root_finders;
+pub mod solver;
@@ -1,9 +1,23 @@
+use exercise_2::interval as enclosure;
+
 use crate::basic::{IntInterval, Interval, Scalar};
 
 pub fn check_root(f: impl Fn(Scalar) -> Scalar, interval: &Interval) -> bool {
     let start_value = f(interval.start);
     let end_value = f(interval.end);
-    return start_value * end_value <= Scalar::from(0);
+    start_value * end_value <= Scalar::from(0)
+}
+
+// proves a root of a continuous f, f evaluated on intervals has to enclose
+// f, so a sign change of the enclosures at the ends is a sign change of f
+pub fn check_root_enclosed(
+    f: impl Fn(enclosure::Interval) -> enclosure::Interval,
+    interval: &Interval,
+) -> bool {
+    let start_value = f(enclosure::Interval::point(interval.start));
+    let end_value = f(enclosure::Interval::point(interval.end));
+    (start_value.hi() <= 0. && end_value.lo() >= 0.)
+        || (start_value.lo() >= 0. && end_value.hi() <= 0.)
 }
 
 #[allow(dead_code)]
@@ -1,17 +1,31 @@
-use basic::{Interval, Scalar};
-use exercise_2::matrix::dense::DenseRowMatrix;
-use newton_method::newton_method;
-use newton_method_n::newton_method_n;
+use std::{cell::Cell, env::args};
 
-use crate::newton_method_n::newton_method_n_interpolated;
+use exercise_2::{
+    basic::{ElementaryOps, Numerical},
+    dual::Dual,
+    interval as enclosure,
+    matrix::dense::DenseRowMatrix,
+};
+use exercise_3::{
+    basic::{Interval, Scalar},
+    broyden::{broyden_method_differences, BroydenUpdate},
+    globalisation::{newton_method_n_globalised, Globalisation, LineSearch, TrustRegion},
+    jacobian::{Difference, FiniteDifference, SparsityPattern},
+    localize_root::check_root_enclosed,
+    newton_method::{newton_method, newton_method_autodiff},
+    newton_method_n::{
+        newton_method_n, newton_method_n_autodiff, newton_method_n_differences,
+        newton_method_n_interpolated,
+    },
+    root_finders::{Bisection, Brent, Illinois, Newton, Ridders, ScalarRootFinder, Secant},
+    solver::{NewtonOptions, NewtonResult},
+};
 
-pub mod basic;
-pub mod localize_root;
-pub mod newton_method;
-pub mod newton_method_n;
-
-fn task_f(x: Scalar) -> Scalar {
-    (Scalar::from(0.5) * x + Scalar::from(0.2)).tan() - x * x
+fn task_f<S>(x: S) -> S
+where
+    S: Numerical + ElementaryOps,
+{
+    (S::from(1) / S::from(2) * x + S::from(1) / S::from(5)).tan_trait() - x * x
 }
 
 fn task_fprime(x: Scalar) -> Scalar {
@@ -20,47 +34,84 @@ fn task_fprime(x: Scalar) -> Scalar {
         - Scalar::from(2.0) * x
 }
 
-fn test_newton_method() {
+fn test_newton_method(trace: bool) {
     println!("\x1b[32m=== Scalar newton method ===\x1b[0m");
     let interval = Interval::new(0.0, 9.0);
-    let max_iterations = 100;
-    let answer = newton_method(task_f, task_fprime, interval, 1e-4, max_iterations);
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    let answer = newton_method(task_f, task_fprime, interval, &options);
     match answer {
-        Some(x) => {
+        Ok(report) => {
+            let x = report.root;
             println!("Newton method result = {x}");
-            println!("Residual = {r:.e}", r = task_f(x));
+            println!("Residual = {r:.e}", r = report.residual);
+            println!(
+                "Iterations = {k}, last step = {step:.e}, {termination}",
+                k = report.iterations,
+                step = report.step,
+                termination = report.termination
+            );
+            let enclosure = Interval::new(x - options.accuracy, x + options.accuracy);
+            println!(
+                "Root in {enclosure:?} proved = {proved}",
+                proved = check_root_enclosed(task_f::<enclosure::Interval>, &enclosure)
+            );
         }
-        None => println!("Newton method did not terminate in {max_iterations}"),
+        Err(error) => println!("Newton method failed: {error}"),
     }
 }
 
-fn task_fn(v: &Vec<Scalar>) -> Vec<Scalar> {
+fn task_fn<S>(v: &[S]) -> Vec<S>
+where
+    S: Numerical + ElementaryOps,
+{
     let x = v[0];
     let y = v[1];
-    vec![-x + (y + 0.5).sin() - 1.0, (x - 2.0).cos() + y]
+    let half = S::one() / S::from(2);
+    vec![
+        -x + (y + half).sin_trait() - S::one(),
+        (x - S::from(2)).cos_trait() + y,
+    ]
 }
 
-fn task_fnprime(v: &Vec<Scalar>) -> DenseRowMatrix<Scalar> {
+fn task_fnprime(v: &[Scalar]) -> DenseRowMatrix<Scalar> {
     let x = v[0];
     let y = v[1];
     DenseRowMatrix::new(2, vec![-1.0, (y + 0.5).cos(), -(x - 2.0).sin(), 1.0])
 }
 
-fn test_newton_method_n() {
-    println!("\x1b[32m=== Vector newton method ===\x1b[0m");
-    let initial_x = vec![0.0, 0.0];
-    let max_iterations = 100;
-    let answer = newton_method_n(task_fn, task_fnprime, initial_x, 1e-4, max_iterations);
+fn print_report_n(answer: NewtonResult<Vec<Scalar>>) {
     match answer {
-        Some(x) => {
-            println!("Result = {x:?}");
-            println!("Residual = {r:?}", r = task_fn(&x));
+        Ok(report) => {
+            println!("Result = {x:?}", x = report.root);
+            println!("Residual = {r:?}", r = task_fn(&report.root));
+            println!(
+                "Iterations = {k}, last step = {step:.e}, {termination}",
+                k = report.iterations,
+                step = report.step,
+                termination = report.termination
+            );
         }
-        None => println!("Newton method did not terminate in {max_iterations}"),
+        Err(error) => println!("Newton method failed: {error}"),
     }
 }
 
-fn task_fi(lambda: Scalar, v: &Vec<Scalar>) -> Vec<Scalar> {
+fn test_newton_method_n(trace: bool) {
+    println!("\x1b[32m=== Vector newton method ===\x1b[0m");
+    let initial_x = vec![0.0, 0.0];
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    let answer = newton_method_n(task_fn, task_fnprime, initial_x, &options);
+    print_report_n(answer);
+}
+
+fn task_fi(lambda: Scalar, v: &[Scalar]) -> Vec<Scalar> {
     let x = v[0];
     let y = v[1];
     vec![
@@ -69,7 +120,7 @@ fn task_fi(lambda: Scalar, v: &Vec<Scalar>) -> Vec<Scalar> {
     ]
 }
 
-fn task_fiprime(lambda: Scalar, v: &Vec<Scalar>) -> DenseRowMatrix<Scalar> {
+fn task_fiprime(lambda: Scalar, v: &[Scalar]) -> DenseRowMatrix<Scalar> {
     let x = v[0];
     let y = v[1];
     DenseRowMatrix::new(
@@ -83,21 +134,253 @@ fn task_fiprime(lambda: Scalar, v: &Vec<Scalar>) -> DenseRowMatrix<Scalar> {
     )
 }
 
-fn test_newton_method_interpolated() {
+fn test_newton_method_interpolated(trace: bool) {
     println!("\x1b[32m=== Vector interpolated newton method ===\x1b[0m");
-    let max_iterations = 100;
-    let answer = newton_method_n_interpolated(task_fi, task_fiprime, 2, 10, 1e-4, max_iterations);
-    match answer {
-        Some(x) => {
-            println!("Result = {x:?}");
-            println!("Residual = {r:?}", r = task_fn(&x));
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    let answer = newton_method_n_interpolated(task_fi, task_fiprime, 2, 10, &options);
+    print_report_n(answer);
+}
+
+// Broyden's tridiagonal function, F_i = (3 - 2x_i) x_i - x_{i-1} - 2x_{i+1} + 1
+// with x_{-1} = x_n = 0, the jacobian is tridiagonal
+fn task_tridiagonal(v: &[Scalar]) -> Vec<Scalar> {
+    let n = v.len();
+    (0..n)
+        .map(|i| {
+            let previous = if i > 0 { v[i - 1] } else { 0.0 };
+            let next = if i + 1 < n { v[i + 1] } else { 0.0 };
+            (3.0 - 2.0 * v[i]) * v[i] - previous - 2.0 * next + 1.0
+        })
+        .collect()
+}
+
+fn test_newton_method_differences(trace: bool) {
+    println!("\x1b[32m=== Vector newton method with finite differences ===\x1b[0m");
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    for difference in [Difference::Forward, Difference::Central] {
+        println!("{difference:?} differences");
+        let differences = FiniteDifference::new(difference);
+        let answer = newton_method_n_differences(task_fn, &differences, vec![0.0, 0.0], &options);
+        print_report_n(answer);
+    }
+
+    let dimension: usize = 100;
+    let entries: Vec<(usize, usize)> = (0..dimension)
+        .flat_map(|i| [i.saturating_sub(1), i, (i + 1).min(dimension - 1)].map(|j| (i, j)))
+        .collect();
+    let patterns = [
+        ("dense", SparsityPattern::dense(dimension)),
+        (
+            "tridiagonal",
+            SparsityPattern::new(dimension, &entries).expect("the entries are in range"),
+        ),
+    ];
+    for (name, pattern) in patterns {
+        let evaluations = Cell::new(0);
+        let f = |v: &[Scalar]| {
+            evaluations.set(evaluations.get() + 1);
+            task_tridiagonal(v)
+        };
+        let groups = pattern.groups();
+        let differences = FiniteDifference::with_pattern(Difference::Forward, pattern);
+        let answer = newton_method_n_differences(f, &differences, vec![-1.0; dimension], &options);
+        match answer {
+            Ok(report) => println!(
+                "Tridiagonal n={dimension}, {name} pattern with {groups} groups: \
+                 iterations = {k}, residual = {r:.e}, evaluations of f = {e}",
+                k = report.iterations,
+                r = report.residual,
+                e = evaluations.get()
+            ),
+            Err(error) => println!("Newton method failed: {error}"),
+        }
+    }
+}
+
+fn test_newton_method_autodiff(trace: bool) {
+    println!("\x1b[32m=== Newton methods with automatic differentiation ===\x1b[0m");
+    // the hand derived derivative against the dual numbers on [0, 9]
+    let deviation = (0..=900)
+        .map(|k| {
+            let x = Scalar::from(k) / 100.0;
+            let derivative = task_f(Dual::variable(x, 0)).derivative();
+            (task_fprime(x) - derivative).abs() / derivative.abs().max(1.0)
+        })
+        .fold(0.0, Scalar::max);
+    println!("Max relative deviation of task_fprime = {deviation:.e}");
+
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    match newton_method_autodiff(task_f, Interval::new(0.0, 9.0), &options) {
+        Ok(report) => println!(
+            "Newton method result = {x}, residual = {r:.e}, iterations = {k}",
+            x = report.root,
+            r = report.residual,
+            k = report.iterations
+        ),
+        Err(error) => println!("Newton method failed: {error}"),
+    }
+
+    let answer = newton_method_n_autodiff::<2>(task_fn, vec![0.0, 0.0], &options);
+    print_report_n(answer);
+}
+
+fn test_broyden_method(trace: bool) {
+    println!("\x1b[32m=== Broyden method ===\x1b[0m");
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    let dimension: usize = 100;
+    let entries: Vec<(usize, usize)> = (0..dimension)
+        .flat_map(|i| [i.saturating_sub(1), i, (i + 1).min(dimension - 1)].map(|j| (i, j)))
+        .collect();
+
+    for update in [BroydenUpdate::Good, BroydenUpdate::Bad] {
+        println!("{update:?} update");
+        let differences = FiniteDifference::new(Difference::Forward);
+        let answer =
+            broyden_method_differences(task_fn, &differences, update, vec![0.0, 0.0], &options);
+        print_report_n(answer);
+
+        let evaluations = Cell::new(0);
+        let f = |v: &[Scalar]| {
+            evaluations.set(evaluations.get() + 1);
+            task_tridiagonal(v)
+        };
+        let differences = FiniteDifference::with_pattern(
+            Difference::Forward,
+            SparsityPattern::new(dimension, &entries).expect("the entries are in range"),
+        );
+        let answer =
+            broyden_method_differences(f, &differences, update, vec![-1.0; dimension], &options);
+        match answer {
+            Ok(report) => println!(
+                "Tridiagonal n={dimension}: iterations = {k}, residual = {r:.e}, \
+                 evaluations of f = {e}",
+                k = report.iterations,
+                r = report.residual,
+                e = evaluations.get()
+            ),
+            Err(error) => println!("Broyden method failed: {error}"),
+        }
+    }
+}
+
+fn test_globalisation(trace: bool) {
+    println!("\x1b[32m=== Globalised newton method ===\x1b[0m");
+    let options = NewtonOptions {
+        accuracy: 1e-4,
+        trace,
+        ..NewtonOptions::default()
+    };
+    let globalisations = [
+        (
+            "line search",
+            Globalisation::LineSearch(LineSearch::default()),
+        ),
+        (
+            "trust region",
+            Globalisation::TrustRegion(TrustRegion::default()),
+        ),
+    ];
+
+    // plain newton wanders from (5, 5) and does not converge from (-18, 3)
+    for start in [vec![5.0, 5.0], vec![-18.0, 3.0]] {
+        println!("Start at {start:?}");
+        match newton_method_n(task_fn, task_fnprime, start.clone(), &options) {
+            Ok(report) => println!(
+                "plain: iterations = {k}, residual = {r:.e}",
+                k = report.iterations,
+                r = report.residual
+            ),
+            Err(error) => println!("plain: {error}"),
+        }
+        for (name, globalisation) in globalisations.iter() {
+            let answer = newton_method_n_globalised(
+                task_fn,
+                task_fnprime,
+                start.clone(),
+                globalisation,
+                &options,
+            );
+            let longest = answer.steps.iter().copied().fold(0.0, Scalar::max);
+            match answer.result {
+                Ok(report) => println!(
+                    "{name}: iterations = {k}, residual = {r:.e}, longest step = {longest:.2e}",
+                    k = report.iterations,
+                    r = report.residual
+                ),
+                Err(error) => println!("{name}: {error}"),
+            }
+        }
+    }
+}
+
+fn test_root_finders(trace: bool) {
+    println!("\x1b[32m=== Scalar root finders ===\x1b[0m");
+    let options = NewtonOptions {
+        accuracy: 1e-10,
+        trace,
+        ..NewtonOptions::default()
+    };
+    let finders: [&dyn ScalarRootFinder; 6] = [
+        &Bisection,
+        &Secant,
+        &Illinois,
+        &Ridders,
+        &Brent,
+        &Newton {
+            fprime: task_fprime,
+        },
+    ];
+
+    println!(
+        "{:>9} | {:>10} | {:>11} | {:>20} | {:>9}",
+        "method", "iterations", "evaluations", "root", "residual"
+    );
+    for finder in finders {
+        let evaluations = Cell::new(0);
+        let f = |x: Scalar| {
+            evaluations.set(evaluations.get() + 1);
+            task_f(x)
+        };
+        let name = finder.name();
+        match finder.find_root(&f, Interval::new(0.0, 9.0), &options) {
+            Ok(report) => println!(
+                "{name:>9} | {k:>10} | {e:>11} | {x:>20} | {r:>9.2e}",
+                k = report.iterations,
+                e = evaluations.get(),
+                x = report.root,
+                r = report.residual
+            ),
+            Err(error) => println!("{name:>9} | {error}"),
         }
-        None => println!("Newton method did not terminate in {max_iterations}"),
     }
 }
 
 fn main() {
-    test_newton_method();
-    test_newton_method_n();
-    test_newton_method_interpolated();
+    // --trace prints every iteration
+    let trace = args().skip(1).any(|arg| arg == "--trace");
+
+    test_newton_method(trace);
+    test_newton_method_n(trace);
+    test_newton_method_interpolated(trace);
+    test_newton_method_differences(trace);
+    test_newton_method_autodiff(trace);
+    test_broyden_method(trace);
+    test_globalisation(trace);
+    test_root_finders(trace);
 }
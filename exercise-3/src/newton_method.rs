@@ -1,18 +1,26 @@
+use exercise_2::dual::Dual;
+
 use crate::{
     basic::{Interval, Scalar},
     localize_root::check_root,
+    solver::{NewtonOptions, NewtonReport, NewtonResult, Termination},
 };
 
+// a zero derivative gives an infinite or NaN step, which is outside of the
+// interval, so the interval is split instead
 fn newton_iteration(
     f: &impl Fn(Scalar) -> Scalar,
     fprime: &impl Fn(Scalar) -> Scalar,
     x: Scalar,
+    trace: bool,
 ) -> Scalar {
     let fx = f(x);
     let fprimex = fprime(x);
     let x_new = x - fx / fprimex;
-    println!("f(x)={fx}, fprime(x)={fprimex}, x={x}, xnew={x_new}");
-    x - f(x) / fprime(x)
+    if trace {
+        println!("f(x)={fx}, fprime(x)={fprimex}, x={x}, xnew={x_new}");
+    }
+    x_new
 }
 
 #[derive(PartialEq, Eq)]
@@ -36,28 +44,52 @@ fn interval_split(
     }
 }
 
+// Newton steps from the ends of an interval with a sign change of f, the
+// interval is split at the steps that stay inside of it and halved otherwise
 pub fn newton_method(
     f: impl Fn(Scalar) -> Scalar,
     fprime: impl Fn(Scalar) -> Scalar,
     mut interval: Interval,
-    accuracy: Scalar,
-    max_iterations: usize,
-) -> Option<Scalar> {
+    options: &NewtonOptions,
+) -> NewtonResult<Scalar> {
+    let accuracy = options.accuracy;
+    let report = |root: Scalar, iterations, step, termination| {
+        NewtonReport {
+            root,
+            iterations,
+            residual: f(root).abs(),
+            step,
+            termination,
+        }
+        .into_result()
+    };
+
+    if !check_root(&f, &interval) {
+        let width = interval.end - interval.start;
+        return report(interval.middle(), 0, width, Termination::NoSignChange);
+    }
+
     let mut previous_iteration = SplitAction::None;
+    let mut step = interval.end - interval.start;
 
-    for k in 0..max_iterations {
-        println!("k={k}");
+    for k in 0..options.max_iterations {
+        if options.trace {
+            println!("k={k}");
+        }
         // from interval end
         if previous_iteration != SplitAction::ShrinkToEnd {
             let x = interval.end;
-            let x_new = newton_iteration(&f, &fprime, x);
+            let x_new = newton_iteration(&f, &fprime, x, options.trace);
 
             if interval.contains(x_new) {
-                if (x_new - x).abs() < accuracy {
-                    return Some(x_new);
+                step = (x_new - x).abs();
+                if step < accuracy {
+                    return report(x_new, k + 1, step, Termination::Step);
                 } else {
                     (interval, previous_iteration) = interval_split(&f, interval, x_new);
-                    println!("New interval={:?}", interval);
+                    if options.trace {
+                        println!("New interval={:?}", interval);
+                    }
                     continue;
                 }
             }
@@ -66,14 +98,17 @@ pub fn newton_method(
         // from interval start
         if previous_iteration != SplitAction::ShrinkToStart {
             let x = interval.start;
-            let x_new = newton_iteration(&f, &fprime, x);
+            let x_new = newton_iteration(&f, &fprime, x, options.trace);
 
             if interval.contains(x_new) {
-                if (x_new - x).abs() < accuracy {
-                    return Some(x_new);
+                step = (x_new - x).abs();
+                if step < accuracy {
+                    return report(x_new, k + 1, step, Termination::Step);
                 } else {
                     (interval, previous_iteration) = interval_split(&f, interval, x_new);
-                    println!("New interval={:?}", interval);
+                    if options.trace {
+                        println!("New interval={:?}", interval);
+                    }
                     continue;
                 }
             }
@@ -81,16 +116,40 @@ pub fn newton_method(
 
         // split interval
         {
-            println!("Overshot");
+            if options.trace {
+                println!("Overshot");
+            }
             let x_new = interval.middle();
             (interval, previous_iteration) = interval_split(&f, interval, x_new);
-            println!("New interval={:?}", interval);
-            if interval.end - interval.start < accuracy {
-                return Some(interval.middle());
+            if options.trace {
+                println!("New interval={:?}", interval);
+            }
+            step = interval.end - interval.start;
+            if step < accuracy {
+                return report(interval.middle(), k + 1, step, Termination::Interval);
             }
             continue;
         }
     }
 
-    None
+    report(
+        interval.middle(),
+        options.max_iterations,
+        step,
+        Termination::MaxIterations,
+    )
+}
+
+// newton_method with the derivative of f computed by dual numbers
+pub fn newton_method_autodiff(
+    f: impl Fn(Dual) -> Dual,
+    interval: Interval,
+    options: &NewtonOptions,
+) -> NewtonResult<Scalar> {
+    newton_method(
+        |x| f(Dual::constant(x)).value(),
+        |x| f(Dual::variable(x, 0)).derivative(),
+        interval,
+        options,
+    )
 }
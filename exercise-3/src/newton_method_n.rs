@@ -1,111 +1,237 @@
 use exercise_2::{
+    dual::Gradient,
     lu_decomposition::LUDecomposition,
     matrix::{
         column::{ColumnFuncInitializer, ColumnMut, ColumnRef},
         dense::DenseRowMatrix,
-        norms::NormedColumn,
+        norms::{NormedColumn, NormedMatrix},
     },
 };
 
-use crate::basic::Scalar;
+use crate::{
+    basic::Scalar,
+    jacobian::FiniteDifference,
+    solver::{NewtonError, NewtonOptions, NewtonReport, NewtonResult, Termination},
+};
 
-// returns -\Delta x
-fn newton_diff(
-    f: &impl Fn(&Vec<Scalar>) -> Vec<Scalar>,
-    fprime: &impl Fn(&Vec<Scalar>) -> DenseRowMatrix<Scalar>,
-    x: &Vec<Scalar>,
-) -> Vec<Scalar> {
-    let jacobian = fprime(x);
+// None when the jacobian is singular, the smallest pivot is at the rounding
+// error of the largest row
+pub(crate) fn decompose_jacobian(
+    jacobian: DenseRowMatrix<Scalar>,
+) -> Option<LUDecomposition<DenseRowMatrix<Scalar>>> {
+    let scale = jacobian.norm_inf();
     let decomposition = LUDecomposition::calculate(jacobian);
+    let pivot = decomposition.min_pivot();
+    if pivot.is_nan() || pivot <= Scalar::EPSILON * scale {
+        return None;
+    }
+    Some(decomposition)
+}
+
+// returns -\Delta x, None when the jacobian is singular
+fn newton_diff(
+    f: &impl Fn(&[Scalar]) -> Vec<Scalar>,
+    fprime: &impl Fn(&[Scalar]) -> DenseRowMatrix<Scalar>,
+    x: &[Scalar],
+) -> Option<Vec<Scalar>> {
+    let decomposition = decompose_jacobian(fprime(x))?;
     let mut v = f(x);
     decomposition.solve(&mut v);
-    v
+    Some(v)
 }
 
 pub fn newton_method_n(
-    f: impl Fn(&Vec<Scalar>) -> Vec<Scalar>,
-    fprime: impl Fn(&Vec<Scalar>) -> DenseRowMatrix<Scalar>,
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    fprime: impl Fn(&[Scalar]) -> DenseRowMatrix<Scalar>,
     mut x: Vec<Scalar>,
-    accuracy: Scalar,
-    max_steps: usize,
-) -> Option<Vec<Scalar>> {
-    for k in 0..max_steps {
-        println!("k={k}");
-        let x_diff = newton_diff(&f, &fprime, &x);
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
+    let report = |root: Vec<Scalar>, iterations, step, termination| {
+        let residual = f(&root).norm_inf();
+        NewtonReport {
+            root,
+            iterations,
+            residual,
+            step,
+            termination,
+        }
+        .into_result()
+    };
+
+    // no step is taken yet
+    let mut step = Scalar::INFINITY;
+
+    for k in 0..options.max_iterations {
+        if options.trace {
+            println!("k={k}");
+        }
+        let Some(x_diff) = newton_diff(&f, &fprime, &x) else {
+            return report(x, k, step, Termination::SingularJacobian);
+        };
+        // the report keeps the last finite step
+        if !x_diff.norm_inf().is_finite() {
+            return report(x, k, step, Termination::NotFinite);
+        }
+        step = x_diff.norm_inf();
+
         let x_new = Vec::<Scalar>::new_func(x.dimension(), |i| x.at(i) - x_diff.at(i));
 
-        println!("New x = {x_new:?}, residue {r:?}", r = f(&x_new));
+        if options.trace {
+            println!("New x = {x_new:?}, residue {r:?}", r = f(&x_new));
+        }
 
-        if x_diff.norm_inf() < accuracy {
-            return Some(x_new);
+        if step < options.accuracy {
+            return report(x_new, k + 1, step, Termination::Step);
         } else {
             x = x_new;
         }
     }
 
-    None
+    report(x, options.max_iterations, step, Termination::MaxIterations)
+}
+
+// newton_method_n with the jacobian approximated by finite differences
+pub fn newton_method_n_differences(
+    f: impl Fn(&[Scalar]) -> Vec<Scalar>,
+    differences: &FiniteDifference,
+    x: Vec<Scalar>,
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
+    if differences.check(x.len()).is_err() {
+        return NewtonReport {
+            residual: f(&x).norm_inf(),
+            root: x,
+            iterations: 0,
+            step: Scalar::INFINITY,
+            termination: Termination::PatternMismatch,
+        }
+        .into_result();
+    }
+    let jacobian = |v: &[Scalar]| {
+        differences
+            .jacobian(&f, v)
+            .expect("the pattern is checked against x")
+    };
+    newton_method_n(&f, jacobian, x, options)
+}
+
+// newton_method_n with the jacobian of f computed by gradients, the system
+// has N equations and N variables
+pub fn newton_method_n_autodiff<const N: usize>(
+    f: impl Fn(&[Gradient<N>]) -> Vec<Gradient<N>>,
+    x: Vec<Scalar>,
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
+    assert_eq!(x.len(), N);
+    newton_method_n(
+        |v| {
+            let constants: Vec<Gradient<N>> = v.iter().map(|x| Gradient::constant(*x)).collect();
+            f(&constants).iter().map(Gradient::value).collect()
+        },
+        |v| {
+            let variables: Vec<Gradient<N>> = v
+                .iter()
+                .enumerate()
+                .map(|(i, x)| Gradient::variable(*x, i))
+                .collect();
+            let rows = f(&variables);
+            DenseRowMatrix::new(N, rows.iter().flat_map(Gradient::gradient).collect())
+        },
+        x,
+        options,
+    )
 }
 
+// the iterations of all stages are counted in the report
 pub fn newton_method_n_staged(
-    f: impl Fn(u32, &Vec<Scalar>) -> Vec<Scalar>,
-    fprime: impl Fn(u32, &Vec<Scalar>) -> DenseRowMatrix<Scalar>,
+    f: impl Fn(u32, &[Scalar]) -> Vec<Scalar>,
+    fprime: impl Fn(u32, &[Scalar]) -> DenseRowMatrix<Scalar>,
     dimension: usize,
     stages: u32,
-    accuracy: Scalar,
-    max_steps: usize,
-) -> Option<Vec<Scalar>> {
-    println!("stage = 0");
-    let mut x = {
-        let mut initial_dx = newton_diff(
-            &|v| f(0, v),
-            &|v| fprime(0, v),
-            &Vec::new_fill(dimension, 0.0),
-        );
-        for k in 0..dimension {
-            *initial_dx.at_mut(k) = -initial_dx.at(k);
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
+    if options.trace {
+        println!("stage = 0");
+    }
+    let origin = Vec::new_fill(dimension, 0.0);
+    let mut x = match newton_diff(&|v| f(0, v), &|v| fprime(0, v), &origin) {
+        Some(mut initial_dx) => {
+            for k in 0..dimension {
+                *initial_dx.at_mut(k) = -initial_dx.at(k);
+            }
+            initial_dx
+        }
+        None => {
+            let residual = f(0, &origin).norm_inf();
+            return NewtonReport {
+                root: origin,
+                iterations: 0,
+                residual,
+                step: Scalar::INFINITY,
+                termination: Termination::SingularJacobian,
+            }
+            .into_result();
         }
-        initial_dx
     };
-    println!("Initial estimate = {x:?}");
+    if options.trace {
+        println!("Initial estimate = {x:?}");
+    }
+
+    let mut iterations = 0;
+    let mut last = None;
 
     for stage in 1..=stages {
-        println!("stage = {stage}");
+        if options.trace {
+            println!("stage = {stage}");
+        }
 
-        let x_new_result = newton_method_n(
-            |v| f(stage, v),
-            |v| fprime(stage, v),
-            x.clone(),
-            accuracy,
-            max_steps,
-        );
+        let x_new_result =
+            newton_method_n(|v| f(stage, v), |v| fprime(stage, v), x.clone(), options);
 
-        println!("New value = {x_new_result:?}");
+        if options.trace {
+            println!("New value = {x_new_result:?}");
+        }
 
         match x_new_result {
-            Some(x_new) => {
-                x = x_new;
+            Ok(mut report) => {
+                iterations += report.iterations;
+                report.iterations = iterations;
+                x = report.root.clone();
+                last = Some(report);
+            }
+            Err(NewtonError { mut report }) => {
+                report.iterations += iterations;
+                return Err(NewtonError { report });
             }
-            None => return None,
         }
     }
 
-    Some(x)
+    match last {
+        Some(report) => Ok(report),
+        // no stages, the initial estimate is the answer, it is one step from
+        // the origin
+        None => Ok(NewtonReport {
+            residual: f(0, &x).norm_inf(),
+            step: x.norm_inf(),
+            root: x,
+            iterations: 1,
+            termination: Termination::Step,
+        }),
+    }
 }
 
 pub fn newton_method_n_interpolated(
-    f: impl Fn(Scalar, &Vec<Scalar>) -> Vec<Scalar>,
-    fprime: impl Fn(Scalar, &Vec<Scalar>) -> DenseRowMatrix<Scalar>,
+    f: impl Fn(Scalar, &[Scalar]) -> Vec<Scalar>,
+    fprime: impl Fn(Scalar, &[Scalar]) -> DenseRowMatrix<Scalar>,
     dimension: usize,
     stages: u32,
-    accuracy: Scalar,
-    max_steps: usize,
-) -> Option<Vec<Scalar>> {
+    options: &NewtonOptions,
+) -> NewtonResult<Vec<Scalar>> {
     newton_method_n_staged(
         |stage, v| f(Scalar::from(stage) / Scalar::from(stages), v),
         |stage, v| fprime(Scalar::from(stage) / Scalar::from(stages), v),
         dimension,
         stages,
-        accuracy,
-        max_steps,
+        options,
     )
 }
@@ -0,0 +1,400 @@
+use crate::{
+    basic::{Interval, Scalar},
+    newton_method::newton_method,
+    solver::{NewtonOptions, NewtonReport, NewtonResult, Termination},
+};
+
+// A method for a root of f on an interval, the accuracy and the iteration
+// limit of the options apply to all of them. The bracketing methods need a
+// sign change of f at the ends and keep one inside of the interval.
+pub trait ScalarRootFinder {
+    fn name(&self) -> &'static str;
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar>;
+}
+
+// halves the interval, one evaluation of f per bit
+#[derive(Clone, Copy, Debug)]
+pub struct Bisection;
+
+// x_new = x - f(x) (x - x_old) / (f(x) - f(x_old)) from the ends of the
+// interval, not bracketing, so it can leave the interval
+#[derive(Clone, Copy, Debug)]
+pub struct Secant;
+
+// regula falsi with the value at the end kept twice in a row halved, so that
+// both ends move
+#[derive(Clone, Copy, Debug)]
+pub struct Illinois;
+
+// the exponential through the ends and the middle, two evaluations of f per
+// iteration with quadratic convergence
+#[derive(Clone, Copy, Debug)]
+pub struct Ridders;
+
+// inverse quadratic interpolation and secant steps, bisection when they fall
+// outside of the interval or do not shrink it fast enough
+#[derive(Clone, Copy, Debug)]
+pub struct Brent;
+
+// the hybrid newton_method with the derivative fprime
+#[derive(Clone, Copy, Debug)]
+pub struct Newton<D> {
+    pub fprime: D,
+}
+
+fn report(
+    f: &dyn Fn(Scalar) -> Scalar,
+    root: Scalar,
+    iterations: usize,
+    step: Scalar,
+    termination: Termination,
+) -> NewtonResult<Scalar> {
+    NewtonReport {
+        root,
+        iterations,
+        residual: f(root).abs(),
+        step,
+        termination,
+    }
+    .into_result()
+}
+
+// same sign, zero counts as both
+fn same_sign(x: Scalar, y: Scalar) -> bool {
+    x * y > 0.0
+}
+
+impl ScalarRootFinder for Bisection {
+    fn name(&self) -> &'static str {
+        "bisection"
+    }
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar> {
+        let (mut a, mut b) = (interval.start, interval.end);
+        let mut fa = f(a);
+        if same_sign(fa, f(b)) {
+            let width = interval.end - interval.start;
+            return report(f, interval.middle(), 0, width, Termination::NoSignChange);
+        }
+
+        let mut step = interval.end - interval.start;
+        for k in 0..options.max_iterations {
+            let middle = (a + b) / 2.0;
+            let f_middle = f(middle);
+            if same_sign(fa, f_middle) {
+                a = middle;
+                fa = f_middle;
+            } else {
+                b = middle;
+            }
+            if options.trace {
+                println!("k={k}, interval=[{a}, {b}]");
+            }
+
+            step = b - a;
+            // the middle is the root when f is zero there
+            if f_middle == 0.0 {
+                return report(f, middle, k + 1, step, Termination::Interval);
+            }
+            if step < options.accuracy {
+                return report(f, (a + b) / 2.0, k + 1, step, Termination::Interval);
+            }
+        }
+
+        report(
+            f,
+            (a + b) / 2.0,
+            options.max_iterations,
+            step,
+            Termination::MaxIterations,
+        )
+    }
+}
+
+impl ScalarRootFinder for Secant {
+    fn name(&self) -> &'static str {
+        "secant"
+    }
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar> {
+        let (mut x_old, mut x) = (interval.start, interval.end);
+        let (mut fx_old, mut fx) = (f(x_old), f(x));
+
+        let mut step = interval.end - interval.start;
+        for k in 0..options.max_iterations {
+            let x_new = x - fx * (x - x_old) / (fx - fx_old);
+            if options.trace {
+                println!("k={k}, x={x}, f(x)={fx}, xnew={x_new}");
+            }
+            if !x_new.is_finite() {
+                return report(f, x, k, step, Termination::NotFinite);
+            }
+
+            step = (x_new - x).abs();
+            if step < options.accuracy {
+                return report(f, x_new, k + 1, step, Termination::Step);
+            }
+            (x_old, fx_old) = (x, fx);
+            x = x_new;
+            fx = f(x);
+        }
+
+        report(
+            f,
+            x,
+            options.max_iterations,
+            step,
+            Termination::MaxIterations,
+        )
+    }
+}
+
+impl ScalarRootFinder for Illinois {
+    fn name(&self) -> &'static str {
+        "illinois"
+    }
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar> {
+        let (mut a, mut b) = (interval.start, interval.end);
+        let (mut fa, mut fb) = (f(a), f(b));
+        if same_sign(fa, fb) {
+            let width = interval.end - interval.start;
+            return report(f, interval.middle(), 0, width, Termination::NoSignChange);
+        }
+
+        // the end replaced by the last iteration, -1 for a and 1 for b
+        let mut side = 0;
+        let mut step = interval.end - interval.start;
+        for k in 0..options.max_iterations {
+            let x = (a * fb - b * fa) / (fb - fa);
+            let fx = f(x);
+            if same_sign(fx, fb) {
+                b = x;
+                fb = fx;
+                if side == 1 {
+                    fa /= 2.0;
+                }
+                side = 1;
+            } else {
+                a = x;
+                fa = fx;
+                if side == -1 {
+                    fb /= 2.0;
+                }
+                side = -1;
+            }
+            if options.trace {
+                println!("k={k}, interval=[{a}, {b}]");
+            }
+
+            step = b - a;
+            if step < options.accuracy || fx == 0.0 {
+                return report(f, x, k + 1, step, Termination::Interval);
+            }
+        }
+
+        report(
+            f,
+            (a + b) / 2.0,
+            options.max_iterations,
+            step,
+            Termination::MaxIterations,
+        )
+    }
+}
+
+impl ScalarRootFinder for Ridders {
+    fn name(&self) -> &'static str {
+        "ridders"
+    }
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar> {
+        let (mut a, mut b) = (interval.start, interval.end);
+        let (mut fa, mut fb) = (f(a), f(b));
+        if same_sign(fa, fb) {
+            let width = interval.end - interval.start;
+            return report(f, interval.middle(), 0, width, Termination::NoSignChange);
+        }
+
+        let mut step = interval.end - interval.start;
+        for k in 0..options.max_iterations {
+            let middle = (a + b) / 2.0;
+            let f_middle = f(middle);
+            // x is the root of the line through the three points of f e^(Q x),
+            // it is in [a, b] as |f_middle| <= root for fa fb < 0
+            let root = (f_middle * f_middle - fa * fb).sqrt();
+            let x = if root == 0.0 {
+                middle
+            } else {
+                middle + (middle - a) * (fa - fb).signum() * f_middle / root
+            };
+            let fx = f(x);
+
+            // the shortest interval with a sign change
+            if !same_sign(f_middle, fx) {
+                (a, fa, b, fb) = (middle, f_middle, x, fx);
+            } else if !same_sign(fa, fx) {
+                (b, fb) = (x, fx);
+            } else {
+                (a, fa) = (x, fx);
+            }
+            if options.trace {
+                println!("k={k}, interval=[{a}, {b}]");
+            }
+
+            step = (b - a).abs();
+            if step < options.accuracy || fx == 0.0 {
+                return report(f, x, k + 1, step, Termination::Interval);
+            }
+        }
+
+        report(
+            f,
+            (a + b) / 2.0,
+            options.max_iterations,
+            step,
+            Termination::MaxIterations,
+        )
+    }
+}
+
+impl ScalarRootFinder for Brent {
+    fn name(&self) -> &'static str {
+        "brent"
+    }
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar> {
+        // b is the best estimate, the root is between b and c, a is the
+        // previous b
+        let (mut a, mut b) = (interval.start, interval.end);
+        let (mut fa, mut fb) = (f(a), f(b));
+        if same_sign(fa, fb) {
+            let width = interval.end - interval.start;
+            return report(f, interval.middle(), 0, width, Termination::NoSignChange);
+        }
+        let (mut c, mut fc) = (b, fb);
+        // the last step and the one before it
+        let (mut d, mut e) = (b - a, b - a);
+
+        let mut step = interval.end - interval.start;
+        for k in 0..options.max_iterations {
+            if same_sign(fb, fc) {
+                (c, fc) = (a, fa);
+                d = b - a;
+                e = d;
+            }
+            if fc.abs() < fb.abs() {
+                (a, fa) = (b, fb);
+                (b, fb) = (c, fc);
+                (c, fc) = (a, fa);
+            }
+            if options.trace {
+                println!("k={k}, b={b}, c={c}");
+            }
+
+            // stops when |c - b| is below the accuracy up to rounding of b
+            let tolerance = 2.0 * Scalar::EPSILON * b.abs() + options.accuracy / 2.0;
+            let half = (c - b) / 2.0;
+            step = (c - b).abs();
+            if half.abs() <= tolerance || fb == 0.0 {
+                return report(f, b, k, step, Termination::Interval);
+            }
+
+            if e.abs() >= tolerance && fa.abs() > fb.abs() {
+                // secant for a == c, inverse quadratic interpolation otherwise,
+                // the step is p / q
+                let s = fb / fa;
+                let (mut p, mut q) = if a == c {
+                    (2.0 * half * s, 1.0 - s)
+                } else {
+                    let q = fa / fc;
+                    let r = fb / fc;
+                    (
+                        s * (2.0 * half * q * (q - r) - (b - a) * (r - 1.0)),
+                        (q - 1.0) * (r - 1.0) * (s - 1.0),
+                    )
+                };
+                if p > 0.0 {
+                    q = -q;
+                } else {
+                    p = -p;
+                }
+
+                // the step has to stay within 3/4 of the interval and be
+                // shorter than half of the step before the last one
+                if 2.0 * p < (3.0 * half * q - (tolerance * q).abs()).min((e * q).abs()) {
+                    e = d;
+                    d = p / q;
+                } else {
+                    d = half;
+                    e = d;
+                }
+            } else {
+                d = half;
+                e = d;
+            }
+
+            (a, fa) = (b, fb);
+            b += if d.abs() > tolerance {
+                d
+            } else {
+                tolerance.copysign(half)
+            };
+            fb = f(b);
+        }
+
+        report(
+            f,
+            b,
+            options.max_iterations,
+            step,
+            Termination::MaxIterations,
+        )
+    }
+}
+
+impl<D: Fn(Scalar) -> Scalar> ScalarRootFinder for Newton<D> {
+    fn name(&self) -> &'static str {
+        "newton"
+    }
+
+    fn find_root(
+        &self,
+        f: &dyn Fn(Scalar) -> Scalar,
+        interval: Interval,
+        options: &NewtonOptions,
+    ) -> NewtonResult<Scalar> {
+        newton_method(f, &self.fprime, interval, options)
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::{self, Display};
+
+use crate::basic::Scalar;
+
+#[derive(Clone, Debug)]
+pub struct NewtonOptions {
+    // the iterations stop when the step or the interval with the root gets
+    // shorter
+    pub accuracy: Scalar,
+    pub max_iterations: usize,
+    // prints every iteration
+    pub trace: bool,
+}
+
+impl Default for NewtonOptions {
+    fn default() -> Self {
+        Self {
+            accuracy: 1e-6,
+            max_iterations: 100,
+            trace: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Termination {
+    // the last step was shorter than the accuracy
+    Step,
+    // the interval with a sign change got shorter than the accuracy
+    Interval,
+    MaxIterations,
+    // the function has the same sign at both ends of the interval
+    NoSignChange,
+    // the smallest pivot of the jacobian is at the rounding error of it
+    SingularJacobian,
+    // the step or the function value overflowed or is NaN
+    NotFinite,
+    // the line search or the trust region found no step decreasing |f|
+    NoDecrease,
+    // w^T y of the Broyden update is at the rounding error of |w| |y|
+    Breakdown,
+    // the sparsity pattern of the finite differences does not fit x
+    PatternMismatch,
+}
+
+impl Termination {
+    pub fn converged(self) -> bool {
+        matches!(self, Termination::Step | Termination::Interval)
+    }
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Termination::Step => write!(f, "the step is below the accuracy"),
+            Termination::Interval => write!(f, "the interval is below the accuracy"),
+            Termination::MaxIterations => write!(f, "the iteration limit is reached"),
+            Termination::NoSignChange => write!(f, "no sign change on the interval"),
+            Termination::SingularJacobian => write!(f, "the jacobian is singular"),
+            Termination::NotFinite => write!(f, "the iterate is not finite"),
+            Termination::NoDecrease => write!(f, "no step decreases the residual"),
+            Termination::Breakdown => write!(f, "the Broyden update broke down"),
+            Termination::PatternMismatch => write!(f, "the sparsity pattern does not fit x"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NewtonReport<Root> {
+    // the last iterate, the root when the method converged
+    pub root: Root,
+    pub iterations: usize,
+    // |f(root)|, the infinity norm for systems
+    pub residual: Scalar,
+    // length of the last step, the infinity norm for systems, the width of the
+    // interval with the root for the bracketing methods
+    pub step: Scalar,
+    pub termination: Termination,
+}
+
+// the method stopped without converging, the report has the last iterate
+#[derive(Clone, Debug)]
+pub struct NewtonError<Root> {
+    pub report: NewtonReport<Root>,
+}
+
+impl<Root> Display for NewtonError<Root> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stopped after {} iterations: {}",
+            self.report.iterations, self.report.termination
+        )
+    }
+}
+
+impl<Root: fmt::Debug> std::error::Error for NewtonError<Root> {}
+
+pub type NewtonResult<Root> = Result<NewtonReport<Root>, NewtonError<Root>>;
+
+impl<Root> NewtonReport<Root> {
+    // Ok for the converged terminations, Err for the others
+    pub fn into_result(self) -> NewtonResult<Root> {
+        if self.termination.converged() {
+            Ok(self)
+        } else {
+            Err(NewtonError { report: self })
+        }
+    }
+}
@@ -0,0 +1,473 @@
+use std::{env, process::Command};
+
+use exercise_2::matrix::{dense::DenseRowMatrix, traits::MatrixRef};
+use exercise_3::{
+    basic::{Interval, Scalar},
+    broyden::{broyden_method, broyden_method_differences, BroydenUpdate},
+    globalisation::{
+        newton_method_n_globalised, Globalisation, GlobalisedResult, LineSearch, TrustRegion,
+    },
+    jacobian::{Difference, FiniteDifference, PatternError, SparsityPattern},
+    newton_method::newton_method,
+    newton_method_n::{newton_method_n, newton_method_n_differences, newton_method_n_interpolated},
+    root_finders::{Bisection, Brent, Illinois, Newton, Ridders, ScalarRootFinder, Secant},
+    solver::{NewtonOptions, NewtonResult, Termination},
+};
+
+fn termination<Root>(result: &NewtonResult<Root>) -> Termination {
+    match result {
+        Ok(report) => report.termination,
+        Err(error) => error.report.termination,
+    }
+}
+
+fn iterations<Root>(result: &NewtonResult<Root>) -> usize {
+    match result {
+        Ok(report) => report.iterations,
+        Err(error) => error.report.iterations,
+    }
+}
+
+fn matrix(dimension: usize, data: &[Scalar]) -> DenseRowMatrix<Scalar> {
+    DenseRowMatrix::new(dimension, data.to_vec())
+}
+
+fn entries(matrix: &DenseRowMatrix<Scalar>) -> Vec<Scalar> {
+    let dimension = matrix.dimension();
+    (0..dimension * dimension)
+        .map(|index| matrix.at(index / dimension, index % dimension))
+        .collect()
+}
+
+// x + t x^3 = 1, x = t, the jacobian is regular at the origin for t = 0
+fn continued_system(t: Scalar, x: &[Scalar]) -> Vec<Scalar> {
+    vec![x[0] + t * x[0] * x[0] * x[0] - 1.0, x[1] - t]
+}
+
+fn continued_jacobian(t: Scalar, x: &[Scalar]) -> DenseRowMatrix<Scalar> {
+    matrix(2, &[1.0 + 3.0 * t * x[0] * x[0], 0.0, 0.0, 1.0])
+}
+
+// x^2 = 2, x = 1 with the root (sqrt(2), 1)
+fn square_root_system(x: &[Scalar]) -> Vec<Scalar> {
+    vec![x[0] * x[0] - 2.0, x[1] - 1.0]
+}
+
+fn square_root_jacobian(x: &[Scalar]) -> DenseRowMatrix<Scalar> {
+    matrix(2, &[2.0 * x[0], 0.0, 0.0, 1.0])
+}
+
+#[test]
+fn systems_stop_at_the_step_below_the_accuracy() {
+    let options = NewtonOptions {
+        accuracy: 1e-10,
+        ..NewtonOptions::default()
+    };
+    let check = |name: &str, result: NewtonResult<Vec<Scalar>>| {
+        let report = result.unwrap_or_else(|error| panic!("{name}: {error}"));
+        assert_eq!(report.termination, Termination::Step, "{name}");
+        assert!(report.step < options.accuracy, "{name}: {}", report.step);
+        assert!((report.root[0] - Scalar::sqrt(2.0)).abs() < 1e-9, "{name}");
+        assert!(report.residual < 1e-9, "{name}: {}", report.residual);
+    };
+
+    let start = vec![1.0, 0.0];
+    check(
+        "newton",
+        newton_method_n(
+            square_root_system,
+            square_root_jacobian,
+            start.clone(),
+            &options,
+        ),
+    );
+    let differences = FiniteDifference::new(Difference::Central);
+    check(
+        "differences",
+        newton_method_n_differences(square_root_system, &differences, start.clone(), &options),
+    );
+    for update in [BroydenUpdate::Good, BroydenUpdate::Bad] {
+        check(
+            "broyden",
+            broyden_method_differences(
+                square_root_system,
+                &differences,
+                update,
+                start.clone(),
+                &options,
+            ),
+        );
+    }
+    for globalisation in [
+        Globalisation::LineSearch(LineSearch::default()),
+        Globalisation::TrustRegion(TrustRegion::default()),
+    ] {
+        let GlobalisedResult { result, steps } = newton_method_n_globalised(
+            square_root_system,
+            square_root_jacobian,
+            start.clone(),
+            &globalisation,
+            &options,
+        );
+        assert_eq!(steps.len(), iterations(&result));
+        assert_eq!(steps.last().copied(), result.as_ref().ok().map(|r| r.step));
+        check("globalised", result);
+    }
+
+    // the stages end at t = 1 with the root of x + x^3 = 1
+    let report =
+        newton_method_n_interpolated(continued_system, continued_jacobian, 2, 4, &options).unwrap();
+    assert_eq!(report.termination, Termination::Step);
+    assert!(continued_system(1.0, &report.root)[0].abs() < 1e-9);
+    assert_eq!(report.root[1], 1.0);
+}
+
+#[test]
+fn newton_method_stops_without_a_sign_change() {
+    let options = NewtonOptions::default();
+    let result = newton_method(
+        |x| x * x + 1.0,
+        |x| 2.0 * x,
+        Interval::new(-1.0, 2.0),
+        &options,
+    );
+    let error = result.unwrap_err();
+    assert_eq!(error.report.termination, Termination::NoSignChange);
+    assert_eq!(error.report.iterations, 0);
+    assert_eq!(error.report.root, 0.5);
+}
+
+#[test]
+fn systems_stop_at_a_singular_jacobian() {
+    let options = NewtonOptions::default();
+    // both equations are x0 + x1 = 1
+    let f = |x: &[Scalar]| vec![x[0] + x[1] - 1.0, x[0] + x[1] - 1.0];
+    let fprime = |_: &[Scalar]| matrix(2, &[1.0, 1.0, 1.0, 1.0]);
+
+    let result = newton_method_n(f, fprime, vec![0.0, 0.0], &options);
+    assert_eq!(termination(&result), Termination::SingularJacobian);
+    assert_eq!(iterations(&result), 0);
+    assert_eq!(result.unwrap_err().report.step, Scalar::INFINITY);
+
+    let result = broyden_method(
+        f,
+        fprime(&[]),
+        BroydenUpdate::Good,
+        vec![0.0, 0.0],
+        &options,
+    );
+    assert_eq!(termination(&result), Termination::SingularJacobian);
+
+    for globalisation in [
+        Globalisation::LineSearch(LineSearch::default()),
+        Globalisation::TrustRegion(TrustRegion::default()),
+    ] {
+        let answer =
+            newton_method_n_globalised(f, fprime, vec![0.0, 0.0], &globalisation, &options);
+        assert_eq!(termination(&answer.result), Termination::SingularJacobian);
+        assert!(answer.steps.is_empty());
+    }
+}
+
+#[test]
+fn systems_stop_when_the_step_is_not_finite() {
+    let options = NewtonOptions::default();
+    // the jacobian is kept at the identity while f overflows, the first step
+    // is finite and the second is not
+    let f = |x: &[Scalar]| vec![1e300 * x[0] * x[0], x[1]];
+    let identity = || matrix(2, &[1.0, 0.0, 0.0, 1.0]);
+
+    let result = newton_method_n(f, |_| identity(), vec![1.0, 0.0], &options);
+    let report = result.unwrap_err().report;
+    assert_eq!(report.termination, Termination::NotFinite);
+    assert_eq!(report.iterations, 1);
+    // the last finite step, not the infinite one
+    assert_eq!(report.step, 1e300);
+    assert_eq!(report.root, vec![1.0 - 1e300, 0.0]);
+
+    let result = broyden_method(
+        f,
+        identity(),
+        BroydenUpdate::Bad,
+        vec![1e200, 0.0],
+        &options,
+    );
+    let report = result.unwrap_err().report;
+    assert_eq!(report.termination, Termination::NotFinite);
+    assert_eq!(report.iterations, 0);
+    assert_eq!(report.step, Scalar::INFINITY);
+}
+
+#[test]
+fn systems_stop_after_the_iteration_limit() {
+    let options = NewtonOptions {
+        max_iterations: 10,
+        ..NewtonOptions::default()
+    };
+    // newton cycles between 0 and 1 on x^3 - 2 x + 2
+    let f = |x: &[Scalar]| vec![x[0] * x[0] * x[0] - 2.0 * x[0] + 2.0];
+    let fprime = |x: &[Scalar]| matrix(1, &[3.0 * x[0] * x[0] - 2.0]);
+
+    let report = newton_method_n(f, fprime, vec![0.0], &options)
+        .unwrap_err()
+        .report;
+    assert_eq!(report.termination, Termination::MaxIterations);
+    assert_eq!(report.iterations, options.max_iterations);
+    assert_eq!(report.root, vec![0.0]);
+    assert_eq!(report.step, 1.0);
+}
+
+#[test]
+fn broyden_stops_when_the_update_breaks_down() {
+    let options = NewtonOptions::default();
+    // the step from -1/2 goes to 1/2 with the same f, so y = 0
+    let f = |x: &[Scalar]| vec![x[0] * x[0] - 0.75];
+    for update in [BroydenUpdate::Good, BroydenUpdate::Bad] {
+        let result = broyden_method(f, matrix(1, &[0.5]), update, vec![-0.5], &options);
+        let report = result.unwrap_err().report;
+        assert_eq!(report.termination, Termination::Breakdown, "{update:?}");
+        assert_eq!(report.iterations, 1);
+        assert_eq!(report.root, vec![0.5]);
+        assert_eq!(report.step, 1.0);
+    }
+}
+
+#[test]
+fn globalised_methods_stop_without_a_decrease() {
+    let options = NewtonOptions::default();
+    // the jacobian has the wrong sign, the step increases |f|
+    let f = |x: &[Scalar]| vec![x[0] - 1.0];
+    let fprime = |_: &[Scalar]| matrix(1, &[-1.0]);
+
+    for globalisation in [
+        Globalisation::LineSearch(LineSearch::default()),
+        Globalisation::TrustRegion(TrustRegion::default()),
+    ] {
+        let answer = newton_method_n_globalised(f, fprime, vec![0.0], &globalisation, &options);
+        let report = answer.result.unwrap_err().report;
+        assert_eq!(
+            report.termination,
+            Termination::NoDecrease,
+            "{globalisation:?}"
+        );
+        // rejected steps are not iterations
+        assert_eq!(report.iterations, 0, "{globalisation:?}");
+        assert_eq!(report.root, vec![0.0]);
+        assert!(answer.steps.is_empty());
+    }
+}
+
+#[test]
+fn root_finders_converge_and_check_the_sign_change() {
+    let f = |x: Scalar| x * x - 2.0;
+    let options = NewtonOptions {
+        accuracy: 1e-10,
+        ..NewtonOptions::default()
+    };
+    let newton = Newton {
+        fprime: |x: Scalar| 2.0 * x,
+    };
+    let finders: [(&dyn ScalarRootFinder, bool); 6] = [
+        (&Bisection, true),
+        (&Secant, false),
+        (&Illinois, true),
+        (&Ridders, true),
+        (&Brent, true),
+        (&newton, true),
+    ];
+
+    for (finder, bracketing) in finders {
+        let name = finder.name();
+        let report = finder
+            .find_root(&f, Interval::new(0.0, 2.0), &options)
+            .unwrap_or_else(|error| panic!("{name}: {error}"));
+        assert!(report.termination.converged(), "{name}");
+        assert!(report.step < options.accuracy, "{name}: {}", report.step);
+        assert!((report.root - Scalar::sqrt(2.0)).abs() < 1e-9, "{name}");
+
+        let result = finder.find_root(&f, Interval::new(2.0, 3.0), &options);
+        if bracketing {
+            let report = result.unwrap_err().report;
+            assert_eq!(report.termination, Termination::NoSignChange, "{name}");
+            assert_eq!(report.iterations, 0, "{name}");
+        }
+    }
+
+    let limited = NewtonOptions {
+        max_iterations: 3,
+        ..options
+    };
+    let report = Bisection
+        .find_root(&f, Interval::new(0.0, 2.0), &limited)
+        .unwrap_err()
+        .report;
+    assert_eq!(report.termination, Termination::MaxIterations);
+    assert_eq!(report.iterations, 3);
+    assert_eq!(report.step, 0.25);
+}
+
+// the zero of x - 1/2 is the first middle of [0, 1], all of the methods land
+// on it exactly
+#[test]
+fn root_finders_return_an_exact_zero() {
+    let f = |x: Scalar| x - 0.5;
+    let options = NewtonOptions::default();
+    let newton = Newton {
+        fprime: |_: Scalar| 1.0,
+    };
+    let finders: [&dyn ScalarRootFinder; 6] =
+        [&Bisection, &Secant, &Illinois, &Ridders, &Brent, &newton];
+
+    for finder in finders {
+        let name = finder.name();
+        let report = finder
+            .find_root(&f, Interval::new(0.0, 1.0), &options)
+            .unwrap_or_else(|error| panic!("{name}: {error}"));
+        assert_eq!(report.root, 0.5, "{name}");
+        assert_eq!(report.residual, 0.0, "{name}");
+    }
+
+    let report = Bisection
+        .find_root(&f, Interval::new(0.0, 1.0), &options)
+        .unwrap();
+    assert_eq!(report.termination, Termination::Interval);
+    assert_eq!(report.iterations, 1);
+}
+
+#[test]
+fn sparsity_patterns_check_the_entries() {
+    assert_eq!(
+        SparsityPattern::new(2, &[(0, 0), (2, 1)]).unwrap_err(),
+        PatternError::OutOfRange {
+            row: 2,
+            column: 1,
+            dimension: 2
+        }
+    );
+
+    let pattern = SparsityPattern::new(3, &[(0, 0), (1, 1), (2, 2), (0, 2)]).unwrap();
+    assert_eq!(pattern.groups(), 2);
+    assert_eq!(SparsityPattern::dense(3).groups(), 3);
+
+    // the pattern gives the same jacobian as no pattern
+    let f = |x: &[Scalar]| vec![x[0] * x[0] + x[2], x[1].sin(), x[2].exp()];
+    let x = [0.5, 1.0, -1.0];
+    for difference in [Difference::Forward, Difference::Central] {
+        let jacobian = |pattern: Option<&SparsityPattern>| {
+            let differences = match pattern {
+                Some(pattern) => FiniteDifference::with_pattern(difference, pattern.clone()),
+                None => FiniteDifference::new(difference),
+            };
+            entries(&differences.jacobian(&f, &x).unwrap())
+        };
+        let dense = jacobian(None);
+        assert_eq!(dense, jacobian(Some(&pattern)), "{difference:?}");
+        assert_eq!(
+            dense,
+            jacobian(Some(&SparsityPattern::dense(3))),
+            "{difference:?}"
+        );
+    }
+
+    // a pattern for two variables with three, the solvers stop at x
+    let differences =
+        FiniteDifference::with_pattern(Difference::Forward, SparsityPattern::dense(2));
+    assert_eq!(
+        differences.jacobian(&f, &x).unwrap_err(),
+        PatternError::DimensionMismatch {
+            pattern: 2,
+            dimension: 3
+        }
+    );
+    let options = NewtonOptions::default();
+    let results = [
+        newton_method_n_differences(f, &differences, x.to_vec(), &options),
+        broyden_method_differences(f, &differences, BroydenUpdate::Good, x.to_vec(), &options),
+    ];
+    for result in results {
+        let report = result.unwrap_err().report;
+        assert_eq!(report.termination, Termination::PatternMismatch);
+        assert_eq!(report.iterations, 0);
+        assert_eq!(report.root, x);
+    }
+}
+
+const TRACE_VARIABLE: &str = "EXERCISE_3_TRACE";
+const BEGIN: &str = "--- begin ---";
+const END: &str = "--- end ---";
+
+// every solver on the square root system and x^2 - 2
+fn run_solvers(trace: bool) {
+    let options = NewtonOptions {
+        trace,
+        ..NewtonOptions::default()
+    };
+    let start = vec![1.0, 0.0];
+    let differences = FiniteDifference::new(Difference::Forward);
+
+    let _ = newton_method_n(
+        square_root_system,
+        square_root_jacobian,
+        start.clone(),
+        &options,
+    );
+    let _ = newton_method_n_interpolated(continued_system, continued_jacobian, 2, 2, &options);
+    let _ = broyden_method_differences(
+        square_root_system,
+        &differences,
+        BroydenUpdate::Good,
+        start.clone(),
+        &options,
+    );
+    for globalisation in [
+        Globalisation::LineSearch(LineSearch::default()),
+        Globalisation::TrustRegion(TrustRegion::default()),
+    ] {
+        let _ = newton_method_n_globalised(
+            square_root_system,
+            square_root_jacobian,
+            start.clone(),
+            &globalisation,
+            &options,
+        );
+    }
+
+    let f = |x: Scalar| x * x - 2.0;
+    let newton = Newton {
+        fprime: |x: Scalar| 2.0 * x,
+    };
+    let finders: [&dyn ScalarRootFinder; 6] =
+        [&Bisection, &Secant, &Illinois, &Ridders, &Brent, &newton];
+    for finder in finders {
+        let _ = finder.find_root(&f, Interval::new(0.0, 2.0), &options);
+    }
+}
+
+// The solvers run in a child process of the test binary, the output of it
+// between the markers is what they printed.
+#[test]
+fn solvers_print_only_with_trace() {
+    const NAME: &str = "solvers_print_only_with_trace";
+    if let Ok(trace) = env::var(TRACE_VARIABLE) {
+        println!("{BEGIN}");
+        run_solvers(trace == "true");
+        println!("{END}");
+        return;
+    }
+
+    let printed = |trace: &str| {
+        let output = Command::new(env::current_exe().unwrap())
+            .args([NAME, "--exact", "--nocapture", "--test-threads=1"])
+            .env(TRACE_VARIABLE, trace)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let begin = stdout.find(BEGIN).expect("the child ran the solvers") + BEGIN.len();
+        let end = stdout.find(END).expect("the child finished the solvers");
+        stdout[begin..end].trim().to_string()
+    };
+
+    assert_eq!(printed("false"), "");
+    assert!(!printed("true").is_empty());
+}
@@ -1,37 +1,33 @@
 use std::mem::swap;
 
-use crate::scalar::*;
-
-fn dot_product(x: &[Scalar], y: &[Scalar]) -> Scalar {
-    debug_assert!(x.len() == y.len());
-
-    let mut r = SCALAR_ZERO;
-
-    for i in 0..x.len() {
-        r += x[i] * y[i];
-    }
+use exercise_2::matrix::{
+    traits::MatrixFunc,
+    triangular::{LowerTriangular, TriangularSolve, UpperTriangular},
+};
 
-    r
-}
+use crate::scalar::*;
 
+// solves the matrix with rows and columns taken in the order of the
+// permutation, which makes it triangular
 fn triangular_solve<const UPPER: bool>(
     matrix: &Vec<Scalar>,
     permutation: &Vec<Index>,
     x: &mut Vec<Scalar>,
 ) {
     let dimension = permutation.len();
+    let permuted = MatrixFunc::new(dimension, |row, column| {
+        matrix[permutation[row] * dimension + permutation[column]]
+    });
+
+    let mut y: Vec<Scalar> = permutation.iter().map(|k| x[*k]).collect();
+    if UPPER {
+        UpperTriangular::new(permuted).solve(&mut y);
+    } else {
+        LowerTriangular::new(permuted).solve(&mut y);
+    }
 
-    for kk in 0..dimension {
-        let k = if UPPER {
-            permutation[dimension - 1 - kk]
-        } else {
-            permutation[kk]
-        };
-
-        let v = x[k];
-        x[k] = SCALAR_ZERO;
-        x[k] = (v - dot_product(&matrix[k * dimension..k * dimension + dimension], &x))
-            / matrix[k * dimension + k];
+    for (k, value) in permutation.iter().zip(y) {
+        x[*k] = value;
     }
 }
 
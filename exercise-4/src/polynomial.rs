@@ -1,36 +1,41 @@
 use crate::scalar::*;
-use std::num::Wrapping;
-
-pub fn poly_at(coefs: &[Scalar], x: Scalar) -> Scalar {
-    let mut xn = SCALAR_ONE;
-    let mut r = SCALAR_ZERO;
-
-    for n in 0..coefs.len() {
-        r += coefs[n] * xn;
-        xn *= x;
-    }
+use exercise_2::{
+    basic::Numerical,
+    summation::{Naive, Summation},
+};
+use std::{iter::successors, num::Wrapping};
+
+// the constructors work in any Numerical scalar, a double-double one gives
+// reference coefficients for the f64 ones
+pub fn poly_at<S: Numerical>(coefs: &[S], x: S) -> S {
+    poly_at_with::<Naive, S>(coefs, x)
+}
 
-    r
+// the terms c_n x^n summed by Method, for the alternating large terms of the
+// interpolation polynomials
+pub fn poly_at_with<Method: Summation, S: Numerical>(coefs: &[S], x: S) -> S {
+    let powers = successors(Some(S::one()), |xn| Some(*xn * x));
+    Method::sum(coefs.iter().zip(powers).map(|(coef, xn)| *coef * xn))
 }
 
 #[derive(Debug, Clone)]
-pub struct Polynomial {
-    pub coefs: Vec<Scalar>,
+pub struct Polynomial<S = Scalar> {
+    pub coefs: Vec<S>,
 }
 
-impl Polynomial {
-    pub fn at(&self, x: Scalar) -> Scalar {
+impl<S: Numerical> Polynomial<S> {
+    pub fn at(&self, x: S) -> S {
         poly_at(&self.coefs, x)
     }
 
-    pub fn new(n: Index) -> Polynomial {
-        let coefs = vec![SCALAR_ZERO; n + 1];
+    pub fn new(n: Index) -> Polynomial<S> {
+        let coefs = vec![S::zero(); n + 1];
         Polynomial { coefs }
     }
 }
 
-pub fn add_root(root: Scalar, mut p: Polynomial) -> Polynomial {
-    let mut store = SCALAR_ZERO;
+pub fn add_root<S: Numerical>(root: S, mut p: Polynomial<S>) -> Polynomial<S> {
+    let mut store = S::zero();
 
     for n in 0..p.coefs.len() {
         let c_prev = store;
@@ -41,9 +46,9 @@ pub fn add_root(root: Scalar, mut p: Polynomial) -> Polynomial {
     p
 }
 
-fn create_lk(points: &Vec<Scalar>, index: Index) -> Polynomial {
+fn create_lk<S: Numerical>(points: &Vec<S>, index: Index) -> Polynomial<S> {
     let mut p = Polynomial::new(points.len() - 1);
-    p.coefs[0] = SCALAR_ONE;
+    p.coefs[0] = S::one();
 
     for k in 0..points.len() {
         if k == index {
@@ -62,14 +67,14 @@ fn create_lk(points: &Vec<Scalar>, index: Index) -> Polynomial {
     p
 }
 
-pub fn create_lagrange(points: &Vec<Scalar>, f: impl Fn(Scalar) -> Scalar) -> Polynomial {
+pub fn create_lagrange<S: Numerical>(points: &Vec<S>, f: impl Fn(S) -> S) -> Polynomial<S> {
     let mut p = Polynomial::new(points.len() - 1);
 
     for k in 0..points.len() {
         let value = f(points[k]);
         let lk = create_lk(points, k);
         for n in 0..p.coefs.len() {
-            p.coefs[n] += lk.coefs[n] * value
+            p.coefs[n] = p.coefs[n] + lk.coefs[n] * value
         }
     }
 
@@ -80,10 +85,10 @@ fn diff_table_index(n: Index, k: Index) -> Index {
     k * n - k * (Wrapping(k) - Wrapping(1)).0 / 2
 }
 
-pub fn create_newton(points: &Vec<Scalar>, f: impl Fn(Scalar) -> Scalar) -> Polynomial {
+pub fn create_newton<S: Numerical>(points: &Vec<S>, f: impl Fn(S) -> S) -> Polynomial<S> {
     // fill the differences table
     let n = points.len();
-    let mut diff_table = vec![SCALAR_ZERO; diff_table_index(n, n - 1) + 1];
+    let mut diff_table = vec![S::zero(); diff_table_index(n, n - 1) + 1];
 
     for k in 0..n {
         diff_table[k] = f(points[k]);
@@ -102,12 +107,12 @@ pub fn create_newton(points: &Vec<Scalar>, f: impl Fn(Scalar) -> Scalar) -> Poly
     // construct the polynomial
     let mut p = Polynomial::new(n - 1);
     let mut omega = Polynomial::new(n - 1);
-    omega.coefs[0] = SCALAR_ONE;
+    omega.coefs[0] = S::one();
 
     for k in 0..n {
         let value = diff_table[diff_table_index(n, k)];
         for i in 0..n {
-            p.coefs[i] += value * omega.coefs[i];
+            p.coefs[i] = p.coefs[i] + value * omega.coefs[i];
         }
 
         omega = add_root(points[k], omega);
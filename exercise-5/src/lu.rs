@@ -1,22 +1,31 @@
 use std::mem::swap;
 
+use exercise_2::{
+    matrix::{
+        traits::MatrixFunc,
+        triangular::{LowerTriangular, TriangularSolve, UpperTriangular},
+    },
+    summation::{Naive, Summation},
+};
+
 use crate::scalar::*;
 
 pub fn dot_product(x: &[Scalar], y: &[Scalar]) -> Scalar {
-    debug_assert!(x.len() == y.len());
-
-    let mut r = SCALAR_ZERO;
+    dot_product_with::<Naive>(x, y)
+}
 
-    for i in 0..x.len() {
-        r += x[i] * y[i];
-    }
+// the products summed by Method, e.g. Neumaier for residuals
+pub fn dot_product_with<Method: Summation>(x: &[Scalar], y: &[Scalar]) -> Scalar {
+    debug_assert!(x.len() == y.len());
 
-    r
+    Method::sum_products(x, y)
 }
 
 const UPPER: Index = 0;
 const LOWER: Index = 1;
 
+// solves the matrix with rows and columns taken in the order of the
+// permutation, which makes it triangular
 fn triangular_solve<const TRIANGULARITY: Index>(
     matrix: &Vec<Scalar>,
     permutation: &Vec<Index>,
@@ -24,18 +33,19 @@ fn triangular_solve<const TRIANGULARITY: Index>(
 ) {
     assert!(TRIANGULARITY == UPPER || TRIANGULARITY == LOWER);
     let dimension = permutation.len();
+    let permuted = MatrixFunc::new(dimension, |row, column| {
+        matrix[permutation[row] * dimension + permutation[column]]
+    });
+
+    let mut y: Vec<Scalar> = permutation.iter().map(|k| x[*k]).collect();
+    if TRIANGULARITY == UPPER {
+        UpperTriangular::new(permuted).solve(&mut y);
+    } else {
+        LowerTriangular::new(permuted).solve(&mut y);
+    }
 
-    for kk in 0..dimension {
-        let k = if TRIANGULARITY == UPPER {
-            permutation[dimension - 1 - kk]
-        } else {
-            permutation[kk]
-        };
-
-        let v = x[k];
-        x[k] = SCALAR_ZERO;
-        x[k] = (v - dot_product(&matrix[k * dimension..k * dimension + dimension], &x))
-            / matrix[k * dimension + k];
+    for (k, value) in permutation.iter().zip(y) {
+        x[*k] = value;
     }
 }
 
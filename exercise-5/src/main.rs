@@ -1,8 +1,11 @@
 use rand::distributions::Distribution;
 use rand_matrix::generate_matrix;
 
+use exercise_2::summation::Neumaier;
+
 use crate::inverse_power_method::inverse_power_method;
-use crate::power_method::power_method;
+use crate::lu::dot_product_with;
+use crate::power_method::{power_method, PowerMethod};
 use crate::qr_algorithm::qr_algorithm;
 use crate::rand_matrix::{rand_scalar, GenerateMatrix};
 use crate::scalar::*;
@@ -14,6 +17,22 @@ mod qr_algorithm;
 mod rand_matrix;
 mod scalar;
 
+// |Av - lambda v|_1 / |v|_1, the products are summed with compensation so
+// that the residual is not lost in the rounding of Av
+fn residual(matrix: &[Scalar], dimension: Index, result: &PowerMethod) -> Scalar {
+    let PowerMethod { value, vector } = result;
+    let mut r = SCALAR_ZERO;
+    let mut norm = SCALAR_ZERO;
+
+    for i in 0..dimension {
+        let row = &matrix[i * dimension..i * dimension + dimension];
+        r += (dot_product_with::<Neumaier>(row, vector) - value * vector[i]).abs();
+        norm += vector[i].abs();
+    }
+
+    r / norm
+}
+
 fn main() {
     // read dimension
     let dimension = {
@@ -57,6 +76,7 @@ fn main() {
             println!("Expected = {expected_result}");
             println!("Result = {eigenvalue}");
             println!("Error = {error:.e}");
+            println!("Residual = {:.e}", residual(&matrix, dimension, &pm));
         }
         None => {
             println!("Did not converge");
@@ -82,6 +102,7 @@ fn main() {
             println!("Initial error = {init_error:.e}");
             println!("Result = {eigenvalue}");
             println!("Error = {error:.e}");
+            println!("Residual = {:.e}", residual(&matrix, dimension, &ipm));
         }
         None => {
             println!("Did not converge");